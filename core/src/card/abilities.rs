@@ -1,6 +1,15 @@
 use crate::{
-    card::mana_cost::{ManaCost, Unit},
-    game_play::battlefield::Battlefield,
+    card::{
+        mana_cost::{ManaCost, Unit},
+        types::{LandSubtypes, Type},
+    },
+    game_play::{
+        battlefield::{Battlefield, InPlayObject},
+        layers::{Affected, Modification},
+        player::PlayerId,
+        target::Resolution,
+    },
+    mana::pool::SpendRestriction,
 };
 
 #[derive(Debug, Clone)]
@@ -11,48 +20,262 @@ pub enum Ability {
     Lifelink,
     FirstStrike,
     DoubleStrike,
-    // TODO: unify triggered abilities
-    Enters(EntersAbility),
-    EndStep(EndStepAbility),
+    Reach,
+    Vigilance,
+    Haste,
+    EntersTapped,
+    // Both halves of the split card can be cast together from hand.
+    Fuse,
+    Triggered(TriggeredAbility),
+    Activated(ActivatedAbility),
     Mana(ManaAbility),
-    // TODO: notion of choosing targets
-    Other(fn(&mut Battlefield)),
+    Static(StaticAbility),
+    // What an instant or sorcery does when it resolves.
+    Spell(Effect),
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
-pub enum EntersAbility {
-    Tapped,
-    Scry { amount: u8 },
-    Surveil { amount: u8 },
-    Discover { amount: u8 },
-    GainLife { amount: u8 },
+#[derive(Debug, Clone)]
+pub struct TriggeredAbility {
+    pub condition: TriggerCondition,
+    pub effect: Effect,
+}
+
+// Activated abilities which are not mana abilities use the stack.
+#[derive(Debug, Clone)]
+pub struct ActivatedAbility {
+    pub cost: AbilityCost,
+    pub effect: Effect,
+    // "Activate only as a sorcery."
+    pub sorcery_speed: bool,
 }
 
+/// Static abilities apply a continuous effect for as long as
+/// the permanent with the ability is on the battlefield.
 #[derive(Debug, Clone)]
-pub enum EndStepAbility {
-    // TODO: targeting (if applicable)
-    Other(fn(&mut Battlefield)),
+pub struct StaticAbility {
+    pub affected: AffectedPermanents,
+    pub modification: Modification,
 }
 
+// Relative to the permanent with the ability.
+// TODO: "enchanted creature" and "equipped creature" (once permanents can be attached).
 #[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AffectedPermanents {
+    Itself,
+    YouControl(PermanentFilter),
+    OthersYouControl(PermanentFilter),
+}
+
+impl AffectedPermanents {
+    pub fn relative_to(&self, object: &InPlayObject) -> Affected {
+        match self {
+            Self::Itself => Affected::Objects(vec![object.id()]),
+            Self::YouControl(filter) => Affected::Permanents {
+                controller: Some(object.controller),
+                filter: filter.clone(),
+                except: None,
+            },
+            Self::OthersYouControl(filter) => Affected::Permanents {
+                controller: Some(object.controller),
+                filter: filter.clone(),
+                except: Some(object.id()),
+            },
+        }
+    }
+}
+
+/// Events an ability can trigger on. Conditions are relative to the object
+/// with the ability: `Enters` means "when this enters", `EndStep` means
+/// "at the beginning of your end step", `Cast` means "whenever you cast a spell", etc.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TriggerCondition {
+    Enters,
+    Dies,
+    Attacks,
+    Upkeep,
+    EndStep,
+    Cast,
+    Draw,
+    // "When you unlock this door" (only abilities of the door being unlocked).
+    UnlockDoor,
+}
+
+#[derive(Debug, Clone)]
+pub enum Effect {
+    Scry {
+        amount: u8,
+    },
+    Surveil {
+        amount: u8,
+    },
+    Discover {
+        amount: u8,
+    },
+    GainLife {
+        amount: u8,
+    },
+    Draw {
+        amount: u8,
+    },
+    // Target creature you control explores.
+    Explore,
+    // Targets are chosen when the spell or ability is put on the stack
+    // and passed to `resolve` in the same order.
+    Other {
+        targets: Vec<TargetSpec>,
+        resolve: fn(&mut Battlefield, &Resolution),
+    },
+}
+
+impl Effect {
+    pub fn targets(&self) -> &[TargetSpec] {
+        match self {
+            Self::Other { targets, .. } => targets,
+            Self::Explore => &[TargetSpec::CreatureYouControl],
+            _ => &[],
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TargetSpec {
+    Player,
+    Opponent,
+    Creature,
+    CreatureYouControl,
+    Permanent,
+    CardInGraveyard,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
 pub struct AbilityCost {
     pub tap: bool,
     pub mana_cost: Option<ManaCost>,
+    // Sacrifice the permanent with the ability.
+    pub sacrifice: bool,
+    // Number of cards to discard
+    pub discard: u8,
+    // Loyalty counters put on (or removed from) a planeswalker, e.g. -2 for "[-2]".
+    // Loyalty abilities can be activated once per turn per permanent, at sorcery speed.
+    pub loyalty: Option<i32>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct ManaAbility {
     pub cost: AbilityCost,
     pub produce: ManaProduction,
+    // "Activate only if ..."
+    pub condition: Option<ActivationCondition>,
+    // "Spend this mana only ..."
+    pub restriction: Option<SpendRestriction>,
+}
+
+impl ManaAbility {
+    // "{T}: Add ..."
+    pub fn tap(produce: ManaProduction) -> Self {
+        Self {
+            cost: AbilityCost {
+                tap: true,
+                ..Default::default()
+            },
+            produce,
+            condition: None,
+            restriction: None,
+        }
+    }
+
+    pub fn can_activate(&self, battlefield: &Battlefield, controller: PlayerId) -> bool {
+        self.condition
+            .as_ref()
+            .is_none_or(|c| c.is_met(battlefield, controller))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ActivationCondition {
+    // E.g. "Activate only if you control a Swamp or a Mountain"
+    ControlsAny(Vec<PermanentFilter>),
+}
+
+impl ActivationCondition {
+    pub fn is_met(&self, battlefield: &Battlefield, controller: PlayerId) -> bool {
+        match self {
+            Self::ControlsAny(filters) => filters
+                .iter()
+                .any(|f| battlefield.count_permanents(controller, f) > 0),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PermanentFilter {
+    Land,
+    LandSubtype(LandSubtypes),
+    Creature,
+}
+
+impl PermanentFilter {
+    pub fn matches(&self, object: &InPlayObject) -> bool {
+        let type_lines = &object.characteristics.type_lines;
+        type_lines.iter().any(|type_line| match self {
+            Self::Land => matches!(type_line, Type::Land(_)),
+            Self::LandSubtype(subtype) => {
+                matches!(type_line, Type::Land(land) if land.subtypes.contains(subtype))
+            }
+            Self::Creature => type_line.creature().is_some(),
+        })
+    }
 }
 
-// TODO: produce multiple kinds of mana
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum ManaProduction {
-    Single { possible: Vec<Unit> },
+    // One mana of any of the possible units
+    Single {
+        possible: Vec<Unit>,
+    },
+    // All of the units together, e.g. {B}{R} or {C}{C}
+    Multiple {
+        units: Vec<Unit>,
+    },
+    // Some amount of mana of any one of the possible units,
+    // e.g. "Add {G} for each Forest you control"
+    Dynamic {
+        possible: Vec<Unit>,
+        amount: ManaAmount,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ManaAmount {
+    // Number of permanents you control matching the filter
+    PermanentsYouControl(PermanentFilter),
 }
 
 impl ManaProduction {
+    // The different combinations of mana that can be produced.
+    pub fn outputs(&self, battlefield: &Battlefield, controller: PlayerId) -> Vec<Vec<Unit>> {
+        match self {
+            Self::Single { possible } => possible.iter().map(|u| vec![u.clone()]).collect(),
+            Self::Multiple { units } => vec![units.clone()],
+            Self::Dynamic { possible, amount } => {
+                let amount = match amount {
+                    ManaAmount::PermanentsYouControl(filter) => {
+                        battlefield.count_permanents(controller, filter)
+                    }
+                };
+                possible.iter().map(|u| vec![u.clone(); amount]).collect()
+            }
+        }
+    }
+
+    // Every kind of mana that can be produced.
+    pub fn units(&self) -> &[Unit] {
+        match self {
+            Self::Single { possible } | Self::Dynamic { possible, .. } => possible,
+            Self::Multiple { units } => units,
+        }
+    }
+
     pub fn colorless() -> Self {
         Self::Single {
             possible: vec![Unit::Colorless],
@@ -101,3 +324,58 @@ impl ManaProduction {
         }
     }
 }
+
+#[test]
+fn test_mana_production() {
+    use crate::{
+        card::{test_card, types::LandProperties},
+        game_play::{agent::Goldfish, player::PlayerState},
+    };
+
+    let land = |name: &str, subtypes: Vec<LandSubtypes>, ability: ManaAbility| {
+        test_card(
+            name,
+            Type::Land(LandProperties {
+                is_basic: false,
+                subtypes,
+            }),
+            vec![Ability::Mana(ability)],
+        )
+    };
+    let verge = ManaAbility {
+        condition: Some(ActivationCondition::ControlsAny(vec![
+            PermanentFilter::LandSubtype(LandSubtypes::Swamp),
+        ])),
+        ..ManaAbility::tap(ManaProduction::red())
+    };
+    let swamp = land(
+        "Swamp",
+        vec![LandSubtypes::Swamp],
+        ManaAbility::tap(ManaProduction::black()),
+    );
+    let mut battlefield = Battlefield::new(vec![PlayerState::new(0, vec![swamp.clone(); 2])]);
+    let player = battlefield.players[0].id;
+    assert!(!verge.can_activate(&battlefield, player));
+
+    for _ in 0..2 {
+        let card = battlefield.players[0].zones.library.pop_front().unwrap();
+        battlefield.play_land(card, &mut Goldfish);
+    }
+    assert!(verge.can_activate(&battlefield, player));
+
+    let dynamic = ManaProduction::Dynamic {
+        possible: vec![Unit::Black, Unit::Green],
+        amount: ManaAmount::PermanentsYouControl(PermanentFilter::LandSubtype(LandSubtypes::Swamp)),
+    };
+    assert_eq!(
+        dynamic.outputs(&battlefield, player),
+        vec![vec![Unit::Black; 2], vec![Unit::Green; 2]]
+    );
+    let multiple = ManaProduction::Multiple {
+        units: vec![Unit::Colorless; 2],
+    };
+    assert_eq!(
+        multiple.outputs(&battlefield, player),
+        vec![vec![Unit::Colorless; 2]]
+    );
+}
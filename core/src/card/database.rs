@@ -0,0 +1,563 @@
+//! Static card data loaded from a Scryfall bulk data file
+//! (e.g. "Oracle Cards" from <https://scryfall.com/docs/api/bulk-data>).
+//! Abilities are not parsed from the oracle text (only some keywords are recognized),
+//! so they still need to be added by hand.
+
+use {
+    crate::card::{
+        Card, CardFace,
+        abilities::Ability,
+        color::Color,
+        mana_cost::{ManaCost, Pip, Unit},
+        registry::CardRegistry,
+        types::{
+            ArtifactProperties, ArtifactSubtypes, BattleProperties, BattleSubtypes,
+            CreatureProperties, CreatureSubtypes, EnchantmentProperties, EnchantmentSubtypes,
+            Expression, LandProperties, LandSubtypes, PlaneswalkerProperties, Power, Toughness,
+            Type,
+        },
+    },
+    serde::Deserialize,
+    std::{fmt, path::Path},
+};
+
+#[derive(Debug)]
+pub enum DatabaseError {
+    Io(std::io::Error),
+    Json(serde_json::Error),
+}
+
+impl fmt::Display for DatabaseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "Failed to read card database: {e}"),
+            Self::Json(e) => write!(f, "Invalid card database: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for DatabaseError {}
+
+impl From<std::io::Error> for DatabaseError {
+    fn from(e: std::io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+impl From<serde_json::Error> for DatabaseError {
+    fn from(e: serde_json::Error) -> Self {
+        Self::Json(e)
+    }
+}
+
+pub fn load(path: impl AsRef<Path>) -> Result<CardRegistry, DatabaseError> {
+    let json = std::fs::read_to_string(path)?;
+    from_json(&json)
+}
+
+// Planeswalkers, battles and kindred cards are loaded along with the other card types.
+// Cards which cannot be represented (e.g. tokens, emblems, an unknown card type,
+// or a combination of types like "Artifact Land") are left out of the registry.
+pub fn from_json(json: &str) -> Result<CardRegistry, DatabaseError> {
+    let cards: Vec<ScryfallCard> = serde_json::from_str(json)?;
+    Ok(cards.iter().filter_map(ScryfallCard::to_card).collect())
+}
+
+// The subset of the Scryfall card object that we use.
+#[derive(Debug, Deserialize)]
+struct ScryfallCard {
+    name: String,
+    layout: String,
+    mana_cost: Option<String>,
+    colors: Option<Vec<String>>,
+    type_line: Option<String>,
+    #[serde(flatten)]
+    stats: ScryfallStats,
+    #[serde(default)]
+    keywords: Vec<String>,
+    card_faces: Option<Vec<ScryfallFace>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ScryfallFace {
+    name: String,
+    mana_cost: Option<String>,
+    colors: Option<Vec<String>>,
+    type_line: Option<String>,
+    #[serde(flatten)]
+    stats: ScryfallStats,
+    oracle_text: Option<String>,
+    // Usually only listed for the whole card.
+    keywords: Option<Vec<String>>,
+}
+
+// The printed numbers of a card or face, depending on its type.
+#[derive(Debug, Deserialize)]
+struct ScryfallStats {
+    power: Option<String>,
+    toughness: Option<String>,
+    loyalty: Option<String>,
+    defense: Option<String>,
+}
+
+impl ScryfallCard {
+    fn to_card(&self) -> Option<Card> {
+        let faces = || -> Option<(CardFace, CardFace)> {
+            let [a, b] = self.card_faces.as_deref()? else {
+                return None;
+            };
+            Some((a.to_face(&self.keywords)?, b.to_face(&self.keywords)?))
+        };
+        match self.layout.as_str() {
+            "split" => faces().map(|(mut a, mut b)| {
+                if self.keywords.iter().any(|k| k == "Fuse") {
+                    a.abilities.push(Ability::Fuse);
+                    b.abilities.push(Ability::Fuse);
+                }
+                Card::Split(a, b)
+            }),
+            "adventure" => {
+                faces().map(|(primary, adventure)| Card::Adventure { primary, adventure })
+            }
+            "omen" => faces().map(|(primary, omen)| Card::Omen { primary, omen }),
+            "modal_dfc" => faces().map(|(front, back)| Card::ModalDoubleFaced { front, back }),
+            "transform" => faces().map(|(front, back)| Card::Transforming { front, back }),
+            // TODO: represent the flipped face once there are flip cards.
+            "flip" => self
+                .card_faces
+                .as_ref()?
+                .first()?
+                .to_face(&self.keywords)
+                .map(Card::Single),
+            "token" | "double_faced_token" | "emblem" | "art_series" | "planar" | "scheme"
+            | "vanguard" | "reversible_card" => None,
+            _ => {
+                let mut face = face(
+                    &self.name,
+                    self.mana_cost.as_deref(),
+                    self.colors.as_deref(),
+                    self.type_line.as_deref()?,
+                    &self.stats,
+                )?;
+                face.abilities = keywords(&self.keywords);
+                Some(Card::Single(face))
+            }
+        }
+    }
+}
+
+impl ScryfallFace {
+    // A face which does not list its own keywords has those of the whole card
+    // which are found in its rules text (e.g. only the back of a transforming card flies).
+    fn to_face(&self, card_keywords: &[String]) -> Option<CardFace> {
+        let mut face = face(
+            &self.name,
+            self.mana_cost.as_deref(),
+            self.colors.as_deref(),
+            self.type_line.as_deref()?,
+            &self.stats,
+        )?;
+        let face_keywords: Vec<String> = match (&self.keywords, &self.oracle_text) {
+            (Some(keywords), _) => keywords.clone(),
+            (None, Some(text)) => (card_keywords.iter())
+                .filter(|k| has_keyword(text, k))
+                .cloned()
+                .collect(),
+            (None, None) => card_keywords.to_vec(),
+        };
+        face.abilities = keywords(&face_keywords);
+        Some(face)
+    }
+}
+
+fn face(
+    name: &str,
+    mana_cost: Option<&str>,
+    colors: Option<&[String]>,
+    type_line: &str,
+    stats: &ScryfallStats,
+) -> Option<CardFace> {
+    let mana_cost = match mana_cost {
+        None | Some("") => None,
+        Some(cost) => Some(cost.parse::<ManaCost>().ok()?),
+    };
+    // Faces of split and adventure cards do not list their own colors.
+    let color = match colors {
+        Some(colors) => colors
+            .iter()
+            .fold(Color::colorless(), |acc, c| acc.and(color(c))),
+        None => {
+            mana_cost
+                .iter()
+                .flat_map(|cost| &cost.pips)
+                .fold(Color::colorless(), |acc, pip| match pip {
+                    Pip::Single(u) | Pip::Phyrexian(u) => acc.and(unit_color(u)),
+                    Pip::Hybrid(a, b) | Pip::PhyrexianHybrid(a, b) => {
+                        acc.and(unit_color(a)).and(unit_color(b))
+                    }
+                })
+        }
+    };
+    let (supertypes, type_line) = parse_type_line(type_line, stats)?;
+    Some(CardFace {
+        name: name.into(),
+        mana_cost,
+        color,
+        is_legendary: supertypes.is_legendary,
+        is_snow: supertypes.is_snow,
+        type_line,
+        abilities: Vec::new(),
+    })
+}
+
+fn color(symbol: &str) -> Color {
+    match symbol {
+        "W" => Color::white(),
+        "U" => Color::blue(),
+        "B" => Color::black(),
+        "R" => Color::red(),
+        "G" => Color::green(),
+        _ => Color::colorless(),
+    }
+}
+
+fn unit_color(unit: &Unit) -> Color {
+    match unit {
+        Unit::White => Color::white(),
+        Unit::Blue => Color::blue(),
+        Unit::Black => Color::black(),
+        Unit::Red => Color::red(),
+        Unit::Green => Color::green(),
+        Unit::Generic { .. } | Unit::X | Unit::Colorless | Unit::Snow => Color::colorless(),
+    }
+}
+
+// Keywords are listed at the start of a line, e.g. "Flying, vigilance"
+// (but not "Creatures you control have flying").
+fn has_keyword(oracle_text: &str, keyword: &str) -> bool {
+    let keyword = keyword.to_lowercase();
+    oracle_text
+        .lines()
+        .flat_map(|line| line.split([',', ';']))
+        .any(|part| part.trim().to_lowercase().starts_with(&keyword))
+}
+
+fn keywords(keywords: &[String]) -> Vec<Ability> {
+    keywords.iter().filter_map(|k| keyword(k)).collect()
+}
+
+fn keyword(keyword: &str) -> Option<Ability> {
+    match keyword {
+        "Flying" => Some(Ability::Flying),
+        "Trample" => Some(Ability::Trample),
+        "Menace" => Some(Ability::Menace),
+        "Lifelink" => Some(Ability::Lifelink),
+        "First strike" => Some(Ability::FirstStrike),
+        "Double strike" => Some(Ability::DoubleStrike),
+        "Reach" => Some(Ability::Reach),
+        "Vigilance" => Some(Ability::Vigilance),
+        "Haste" => Some(Ability::Haste),
+        _ => None,
+    }
+}
+
+// Kindred is handled separately, since it is combined with another card type.
+const CARD_TYPES: [&str; 8] = [
+    "Artifact",
+    "Battle",
+    "Creature",
+    "Enchantment",
+    "Instant",
+    "Land",
+    "Planeswalker",
+    "Sorcery",
+];
+
+struct Supertypes {
+    is_legendary: bool,
+    is_snow: bool,
+}
+
+// E.g. "Legendary Creature — Human Artificer".
+// Returns `None` if the types cannot be represented (e.g. "Artifact Land" or
+// "Enchantment Artifact"), rather than keeping only one of them.
+// Subtypes that we do not have yet are ignored.
+fn parse_type_line(type_line: &str, stats: &ScryfallStats) -> Option<(Supertypes, Type)> {
+    let (types, subtypes) = type_line.split_once('—').unwrap_or((type_line, ""));
+    let types: Vec<&str> = types.split_whitespace().collect();
+    let subtypes: Vec<&str> = subtypes.split_whitespace().collect();
+    let has = |t: &str| types.contains(&t);
+    // E.g. "3"; "X" (and any other value which is not a number) counts as 0.
+    let number = |n: &Option<String>| n.as_deref().and_then(|n| n.parse().ok()).unwrap_or(0);
+
+    let creature_subtypes = || {
+        subtypes
+            .iter()
+            .filter_map(|s| creature_subtype(s))
+            .collect()
+    };
+    let creature = || CreatureProperties {
+        subtypes: creature_subtypes(),
+        power: stats
+            .power
+            .as_deref()
+            .map(parse_power)
+            .unwrap_or(Power::Value(0)),
+        toughness: stats
+            .toughness
+            .as_deref()
+            .map(parse_toughness)
+            .unwrap_or(Toughness::Value(0)),
+    };
+    let artifact = || ArtifactProperties {
+        subtypes: subtypes
+            .iter()
+            .filter_map(|s| artifact_subtype(s))
+            .collect(),
+    };
+    let enchantment = || EnchantmentProperties {
+        subtypes: subtypes
+            .iter()
+            .filter_map(|s| enchantment_subtype(s))
+            .collect(),
+    };
+
+    // In alphabetical order, to match on the combinations we can represent.
+    let card_types: Vec<&str> = CARD_TYPES.into_iter().filter(|t| has(t)).collect();
+    let type_line = match card_types[..] {
+        ["Land"] => Type::Land(LandProperties {
+            is_basic: has("Basic"),
+            subtypes: subtypes.iter().filter_map(|s| land_subtype(s)).collect(),
+        }),
+        ["Artifact", "Creature"] => Type::ArtifactCreature {
+            artifact: artifact(),
+            creature: creature(),
+        },
+        ["Creature", "Enchantment"] => Type::EnchantmentCreature {
+            enchantment: enchantment(),
+            creature: creature(),
+        },
+        ["Creature"] => Type::Creature(creature()),
+        ["Artifact"] => Type::Artifact(artifact()),
+        ["Enchantment"] => Type::Enchantment(enchantment()),
+        ["Instant"] => Type::Instant,
+        ["Sorcery"] => Type::Sorcery,
+        ["Planeswalker"] => Type::Planeswalker(PlaneswalkerProperties {
+            loyalty: number(&stats.loyalty),
+        }),
+        ["Battle"] => Type::Battle(BattleProperties {
+            subtypes: subtypes.iter().filter_map(|s| battle_subtype(s)).collect(),
+            defense: number(&stats.defense),
+        }),
+        _ => return None,
+    };
+    // "Tribal" is the old name of Kindred.
+    let type_line = if (has("Kindred") || has("Tribal")) && type_line.creature().is_none() {
+        Type::Kindred {
+            subtypes: creature_subtypes(),
+            card_type: Box::new(type_line),
+        }
+    } else {
+        type_line
+    };
+    let supertypes = Supertypes {
+        is_legendary: has("Legendary"),
+        is_snow: has("Snow"),
+    };
+    Some((supertypes, type_line))
+}
+
+// E.g. "3", "*" or "*+1"
+fn parse_power(power: &str) -> Power {
+    match power {
+        "*" => Power::Dynamic { modifier: None },
+        "*+1" => Power::Dynamic {
+            modifier: Some(Expression::PlusOne),
+        },
+        _ => power
+            .parse()
+            .map(Power::Value)
+            .unwrap_or(Power::Dynamic { modifier: None }),
+    }
+}
+
+fn parse_toughness(toughness: &str) -> Toughness {
+    match toughness {
+        "*" => Toughness::Dynamic { modifier: None },
+        "*+1" => Toughness::Dynamic {
+            modifier: Some(Expression::PlusOne),
+        },
+        _ => toughness
+            .parse()
+            .map(Toughness::Value)
+            .unwrap_or(Toughness::Dynamic { modifier: None }),
+    }
+}
+
+fn land_subtype(subtype: &str) -> Option<LandSubtypes> {
+    match subtype {
+        "Plains" => Some(LandSubtypes::Plains),
+        "Island" => Some(LandSubtypes::Island),
+        "Swamp" => Some(LandSubtypes::Swamp),
+        "Mountain" => Some(LandSubtypes::Mountain),
+        "Forest" => Some(LandSubtypes::Forest),
+        "Town" => Some(LandSubtypes::Town),
+        "Cave" => Some(LandSubtypes::Cave),
+        "Desert" => Some(LandSubtypes::Desert),
+        _ => None,
+    }
+}
+
+fn creature_subtype(subtype: &str) -> Option<CreatureSubtypes> {
+    match subtype {
+        "Artificer" => Some(CreatureSubtypes::Artificer),
+        "Demon" => Some(CreatureSubtypes::Demon),
+        "Dinosaur" => Some(CreatureSubtypes::Dinosaur),
+        "Dragon" => Some(CreatureSubtypes::Dragon),
+        "Human" => Some(CreatureSubtypes::Human),
+        _ => None,
+    }
+}
+
+fn artifact_subtype(subtype: &str) -> Option<ArtifactSubtypes> {
+    match subtype {
+        "Food" => Some(ArtifactSubtypes::Food),
+        "Treasure" => Some(ArtifactSubtypes::Treasure),
+        "Clue" => Some(ArtifactSubtypes::Clue),
+        "Blood" => Some(ArtifactSubtypes::Blood),
+        "Map" => Some(ArtifactSubtypes::Map),
+        _ => None,
+    }
+}
+
+fn enchantment_subtype(subtype: &str) -> Option<EnchantmentSubtypes> {
+    match subtype {
+        "Room" => Some(EnchantmentSubtypes::Room),
+        _ => None,
+    }
+}
+
+fn battle_subtype(subtype: &str) -> Option<BattleSubtypes> {
+    match subtype {
+        "Siege" => Some(BattleSubtypes::Siege),
+        _ => None,
+    }
+}
+
+#[test]
+fn test_from_json() {
+    let json = r#"[
+        {
+            "name": "Trumpeting Carnosaur",
+            "layout": "normal",
+            "mana_cost": "{4}{R}{R}",
+            "colors": ["R"],
+            "type_line": "Creature — Dinosaur",
+            "power": "7",
+            "toughness": "6",
+            "keywords": ["Discover", "Trample"]
+        },
+        {
+            "name": "Glassworks // Shattered Yard",
+            "layout": "split",
+            "mana_cost": "{1}{R} // {4}{R}",
+            "colors": ["R"],
+            "type_line": "Enchantment — Room // Enchantment — Room",
+            "card_faces": [
+                {"name": "Glassworks", "mana_cost": "{1}{R}", "type_line": "Enchantment — Room"},
+                {"name": "Shattered Yard", "mana_cost": "{4}{R}", "type_line": "Enchantment — Room"}
+            ]
+        },
+        {
+            "name": "Liliana of the Veil",
+            "layout": "normal",
+            "mana_cost": "{1}{B}{B}",
+            "colors": ["B"],
+            "type_line": "Legendary Planeswalker — Liliana",
+            "loyalty": "3"
+        },
+        {
+            "name": "Bitterblossom",
+            "layout": "normal",
+            "mana_cost": "{1}{B}",
+            "colors": ["B"],
+            "type_line": "Kindred Enchantment — Faerie"
+        },
+        {
+            "name": "Seat of the Synod",
+            "layout": "normal",
+            "mana_cost": "",
+            "colors": [],
+            "type_line": "Artifact Land"
+        },
+        {
+            "name": "Delver of Secrets // Insectile Aberration",
+            "layout": "transform",
+            "keywords": ["Flying", "Transform"],
+            "card_faces": [
+                {
+                    "name": "Delver of Secrets",
+                    "mana_cost": "{U}",
+                    "colors": ["U"],
+                    "type_line": "Creature — Human Wizard",
+                    "oracle_text": "At the beginning of your upkeep, look at the top card of your library.",
+                    "power": "1",
+                    "toughness": "1"
+                },
+                {
+                    "name": "Insectile Aberration",
+                    "mana_cost": "",
+                    "colors": ["U"],
+                    "type_line": "Creature — Human Insect",
+                    "oracle_text": "Flying",
+                    "power": "3",
+                    "toughness": "2"
+                }
+            ]
+        }
+    ]"#;
+    let registry = from_json(json).unwrap();
+
+    let Some(Card::Single(carnosaur)) = registry.get("Trumpeting Carnosaur") else {
+        panic!("Expected a single faced card");
+    };
+    assert_eq!(carnosaur.mana_value(), 6);
+    assert!(carnosaur.color.is_red());
+    assert!(matches!(carnosaur.abilities[..], [Ability::Trample]));
+    assert_eq!(
+        carnosaur.type_line,
+        Type::Creature(CreatureProperties {
+            subtypes: vec![CreatureSubtypes::Dinosaur],
+            power: Power::Value(7),
+            toughness: Toughness::Value(6),
+        })
+    );
+
+    let Some(Card::Split(glassworks, _)) = registry.get("Glassworks") else {
+        panic!("Expected a split card");
+    };
+    assert!(glassworks.color.is_red());
+    assert_eq!(glassworks.mana_value(), 2);
+
+    let Some(Card::Single(liliana)) = registry.get("Liliana of the Veil") else {
+        panic!("Expected a single faced card");
+    };
+    assert!(liliana.is_legendary);
+    assert_eq!(
+        liliana.type_line,
+        Type::Planeswalker(PlaneswalkerProperties { loyalty: 3 })
+    );
+    let Some(Card::Single(bitterblossom)) = registry.get("Bitterblossom") else {
+        panic!("Expected a single faced card");
+    };
+    assert!(matches!(bitterblossom.type_line, Type::Kindred { .. }));
+
+    // Only one of the types would be kept, so the card is left out.
+    assert!(registry.get("Seat of the Synod").is_none());
+
+    let Some(Card::Transforming { front, back }) = registry.get("Delver of Secrets") else {
+        panic!("Expected a transforming card");
+    };
+    assert!(front.abilities.is_empty());
+    assert!(matches!(back.abilities[..], [Ability::Flying]));
+}
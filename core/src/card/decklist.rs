@@ -0,0 +1,223 @@
+use {
+    crate::card::{Card, registry::CardRegistry},
+    std::{fmt, str::FromStr},
+};
+
+/// A deck list in the text format exported by MTG Arena (and accepted by MTGO). E.g.
+/// ```text
+/// Companion
+/// 1 Lurrus of the Dream-Den (IKO) 226
+///
+/// Deck
+/// 4 Cease // Desist (MKM) 246
+/// 3 Mountain (SLD) 1471
+///
+/// Sideboard
+/// 2 Duress (FDN) 605
+/// ```
+/// Lists without section headers are read the MTGO way: the main deck,
+/// then a blank line, then the sideboard. Lines starting with `SB:` are also
+/// sideboard entries.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DeckList {
+    pub commander: Vec<DeckEntry>,
+    pub companion: Vec<DeckEntry>,
+    pub main: Vec<DeckEntry>,
+    pub sideboard: Vec<DeckEntry>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeckEntry {
+    pub count: u32,
+    pub name: String,
+    pub set: Option<String>,
+    // Not necessarily a number (e.g. "123a" or "★")
+    pub collector_number: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DeckListError {
+    // Line numbers start from 1.
+    MalformedLine { line: usize, content: String },
+    UnknownCard { name: String },
+}
+
+impl fmt::Display for DeckListError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MalformedLine { line, content } => {
+                write!(f, "Malformed deck list line {line}: {content:?}")
+            }
+            Self::UnknownCard { name } => write!(f, "Unknown card: {name:?}"),
+        }
+    }
+}
+
+impl std::error::Error for DeckListError {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Section {
+    Commander,
+    Companion,
+    Main,
+    Sideboard,
+    // Arena puts the deck name here; it is ignored.
+    About,
+}
+
+impl Section {
+    fn from_header(line: &str) -> Option<Self> {
+        let header = line.strip_suffix(':').unwrap_or(line).trim();
+        match header.to_ascii_lowercase().as_str() {
+            "commander" => Some(Self::Commander),
+            "companion" => Some(Self::Companion),
+            "deck" | "main" | "maindeck" | "main deck" => Some(Self::Main),
+            "sideboard" => Some(Self::Sideboard),
+            "about" => Some(Self::About),
+            _ => None,
+        }
+    }
+}
+
+impl DeckList {
+    // One `Card` per copy of each entry.
+    pub fn resolve(
+        entries: &[DeckEntry],
+        registry: &CardRegistry,
+    ) -> Result<Vec<Card>, DeckListError> {
+        let mut cards = Vec::new();
+        for entry in entries {
+            let card = registry
+                .get(&entry.name)
+                .ok_or_else(|| DeckListError::UnknownCard {
+                    name: entry.name.clone(),
+                })?;
+            cards.extend(std::iter::repeat_n(card, entry.count as usize).cloned());
+        }
+        Ok(cards)
+    }
+
+    pub fn main_deck_cards(&self, registry: &CardRegistry) -> Result<Vec<Card>, DeckListError> {
+        Self::resolve(&self.main, registry)
+    }
+
+    pub fn sideboard_cards(&self, registry: &CardRegistry) -> Result<Vec<Card>, DeckListError> {
+        Self::resolve(&self.sideboard, registry)
+    }
+
+    fn section_mut(&mut self, section: Section) -> &mut Vec<DeckEntry> {
+        match section {
+            Section::Commander => &mut self.commander,
+            Section::Companion => &mut self.companion,
+            Section::Main | Section::About => &mut self.main,
+            Section::Sideboard => &mut self.sideboard,
+        }
+    }
+}
+
+impl FromStr for DeckList {
+    type Err = DeckListError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut list = Self::default();
+        let mut section = Section::Main;
+        let mut has_headers = false;
+        for (i, line) in s.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() {
+                // MTGO style: a blank line separates the main deck from the sideboard.
+                if !has_headers && section == Section::Main && !list.main.is_empty() {
+                    section = Section::Sideboard;
+                }
+                continue;
+            }
+            if let Some(header) = Section::from_header(line) {
+                section = header;
+                has_headers = true;
+                continue;
+            }
+            if section == Section::About {
+                continue;
+            }
+            let (section, line) = match line.strip_prefix("SB:") {
+                Some(rest) => (Section::Sideboard, rest.trim()),
+                None => (section, line),
+            };
+            let entry = parse_entry(line).ok_or_else(|| DeckListError::MalformedLine {
+                line: i + 1,
+                content: line.into(),
+            })?;
+            list.section_mut(section).push(entry);
+        }
+        Ok(list)
+    }
+}
+
+// E.g. "4 Cease // Desist (MKM) 246", "4x Cease // Desist" or "1 Mountain (SLD)".
+fn parse_entry(line: &str) -> Option<DeckEntry> {
+    let (count, rest) = line.split_once(char::is_whitespace)?;
+    let count: u32 = count.strip_suffix('x').unwrap_or(count).parse().ok()?;
+    if count == 0 {
+        return None;
+    }
+    let rest = rest.trim();
+    let (name, set, collector_number) = match rest.rfind(" (") {
+        Some(open) => {
+            let (set, number) = rest[open + 2..].split_once(')')?;
+            let number = number.trim();
+            if set.is_empty() || number.contains(char::is_whitespace) {
+                return None;
+            }
+            let number = (!number.is_empty()).then(|| number.to_string());
+            (rest[..open].trim(), Some(set.to_string()), number)
+        }
+        None => (rest, None, None),
+    };
+    if name.is_empty() {
+        return None;
+    }
+    Some(DeckEntry {
+        count,
+        name: name.into(),
+        set,
+        collector_number,
+    })
+}
+
+#[test]
+fn test_parse_deck_list() {
+    let text = "About\nName Test\n\nCompanion\n1 Lurrus of the Dream-Den (IKO) 226\n\n\
+        Deck\n4 Cease // Desist (MKM) 246\n3 Mountain\n\nSideboard\n2x Duress (FDN)\n";
+    let list: DeckList = text.parse().unwrap();
+    assert_eq!(list.companion.len(), 1);
+    assert_eq!(
+        list.main[0],
+        DeckEntry {
+            count: 4,
+            name: "Cease // Desist".into(),
+            set: Some("MKM".into()),
+            collector_number: Some("246".into()),
+        }
+    );
+    assert_eq!(list.main[1].set, None);
+    assert_eq!(list.sideboard[0].count, 2);
+    assert_eq!(list.sideboard[0].collector_number, None);
+
+    // MTGO style, with the sideboard after a blank line
+    let list: DeckList = "4 Mountain\n\n2 Duress\nSB: 1 Swamp".parse().unwrap();
+    assert_eq!((list.main.len(), list.sideboard.len()), (1, 2));
+
+    assert_eq!(
+        "Deck\n4 Mountain\nMountain".parse::<DeckList>(),
+        Err(DeckListError::MalformedLine {
+            line: 3,
+            content: "Mountain".into()
+        })
+    );
+    assert_eq!(
+        list.main_deck_cards(&CardRegistry::new()).unwrap_err(),
+        DeckListError::UnknownCard {
+            name: "Mountain".into()
+        }
+    );
+}
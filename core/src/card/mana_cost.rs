@@ -1,3 +1,5 @@
+use std::{fmt, str::FromStr};
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct ManaCost {
     pub pips: Vec<Pip>,
@@ -5,7 +7,15 @@ pub struct ManaCost {
 
 impl ManaCost {
     pub fn mana_value(&self) -> u8 {
-        self.pips.iter().map(|p| p.mana_value()).sum()
+        self.as_ref().mana_value()
+    }
+
+    pub fn x_count(&self) -> u8 {
+        self.as_ref().x_count()
+    }
+
+    pub fn mana_value_with_x(&self, x: u8) -> u8 {
+        self.as_ref().mana_value_with_x(x)
     }
 
     pub fn as_ref(&self) -> ManaCostRef<'_> {
@@ -19,8 +29,27 @@ pub struct ManaCostRef<'a> {
 }
 
 impl<'a> ManaCostRef<'a> {
+    // X counts as 0 everywhere except on the stack.
+    // Saturates rather than overflowing for large generic amounts (e.g. {200}{100}).
     pub fn mana_value(&self) -> u8 {
-        self.pips.iter().map(|p| p.mana_value()).sum()
+        self.pips
+            .iter()
+            .fold(0u8, |total, p| total.saturating_add(p.mana_value()))
+    }
+
+    // Number of {X} symbols, e.g. 2 for {X}{X}{R}.
+    pub fn x_count(&self) -> u8 {
+        self.pips
+            .iter()
+            .filter(|p| matches!(p, Pip::Single(Unit::X)))
+            .count() as u8
+    }
+
+    // Mana value of a spell on the stack, where the value of X has been chosen.
+    // Saturates rather than overflowing for large values of X.
+    pub fn mana_value_with_x(&self, x: u8) -> u8 {
+        self.mana_value()
+            .saturating_add(self.x_count().saturating_mul(x))
     }
 }
 
@@ -33,18 +62,47 @@ impl<'a> From<&'a [Pip]> for ManaCostRef<'a> {
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Pip {
     Single(Unit),
+    // Includes "two-brid" symbols like {2/W}, i.e. `Hybrid(Generic { amount: 2 }, White)`.
     Hybrid(Unit, Unit),
+    // Can be paid with the unit or 2 life.
+    Phyrexian(Unit),
+    PhyrexianHybrid(Unit, Unit),
 }
 
 impl Pip {
+    // Hybrid symbols count as the larger half (e.g. 2 for {2/W}).
     pub fn mana_value(&self) -> u8 {
         match self {
-            Self::Single(unit) => unit.mana_value(),
-            Self::Hybrid(a, b) => core::cmp::max(a.mana_value(), b.mana_value()),
+            Self::Single(unit) | Self::Phyrexian(unit) => unit.mana_value(),
+            Self::Hybrid(a, b) | Self::PhyrexianHybrid(a, b) => {
+                core::cmp::max(a.mana_value(), b.mana_value())
+            }
+        }
+    }
+
+    // The different ways this pip can be paid for, given the chosen value of X.
+    pub fn payment_options(&self, x: u8) -> Vec<Payment> {
+        match self {
+            Self::Single(unit) => vec![unit.payment(x)],
+            Self::Hybrid(a, b) => vec![a.payment(x), b.payment(x)],
+            Self::Phyrexian(unit) => vec![unit.payment(x), Payment::Life { amount: 2 }],
+            Self::PhyrexianHybrid(a, b) => {
+                vec![a.payment(x), b.payment(x), Payment::Life { amount: 2 }]
+            }
         }
     }
 }
 
+/// One way of paying for a pip.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Payment {
+    // Mana of any type
+    Generic { amount: u8 },
+    // One mana of the given color, colorless mana or mana from a snow source.
+    Mana(Unit),
+    Life { amount: u8 },
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Unit {
     Generic { amount: u8 },
@@ -55,6 +113,8 @@ pub enum Unit {
     Red,
     Green,
     Colorless,
+    // Mana from a snow source
+    Snow,
 }
 
 impl Unit {
@@ -62,7 +122,219 @@ impl Unit {
         match self {
             Self::Generic { amount } => *amount,
             Self::X => 0,
-            Self::White | Self::Blue | Self::Black | Self::Red | Self::Green | Self::Colorless => 1,
+            Self::White
+            | Self::Blue
+            | Self::Black
+            | Self::Red
+            | Self::Green
+            | Self::Colorless
+            | Self::Snow => 1,
+        }
+    }
+
+    fn payment(&self, x: u8) -> Payment {
+        match self {
+            Self::Generic { amount } => Payment::Generic { amount: *amount },
+            Self::X => Payment::Generic { amount: x },
+            unit => Payment::Mana(unit.clone()),
+        }
+    }
+}
+
+/// A mana symbol (or sequence of symbols) that could not be parsed, e.g. "{Q}".
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidManaSymbol(pub String);
+
+impl fmt::Display for InvalidManaSymbol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Invalid mana symbol: {:?}", self.0)
+    }
+}
+
+impl std::error::Error for InvalidManaSymbol {}
+
+// E.g. "{2}{B}{B/G}". The empty string is a cost with no pips.
+impl FromStr for ManaCost {
+    type Err = InvalidManaSymbol;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut pips = Vec::new();
+        let mut rest = s.trim();
+        while !rest.is_empty() {
+            let end = rest
+                .find('}')
+                .ok_or_else(|| InvalidManaSymbol(rest.into()))?;
+            pips.push(rest[..=end].parse()?);
+            rest = &rest[end + 1..];
+        }
+        Ok(Self { pips })
+    }
+}
+
+impl fmt::Display for ManaCost {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.as_ref().fmt(f)
+    }
+}
+
+impl fmt::Display for ManaCostRef<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.pips.iter().try_for_each(|p| p.fmt(f))
+    }
+}
+
+// A single symbol including the braces, e.g. "{B/P}".
+impl FromStr for Pip {
+    type Err = InvalidManaSymbol;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || InvalidManaSymbol(s.into());
+        let symbol = s
+            .strip_prefix('{')
+            .and_then(|s| s.strip_suffix('}'))
+            .ok_or_else(invalid)?;
+        let parts: Vec<&str> = symbol.split('/').collect();
+        let unit = |part: &str| part.parse::<Unit>().map_err(|_| invalid());
+        // X and snow mana only appear on their own, and the halves of a hybrid symbol differ
+        // (e.g. {W/U} or {2/W}, but not {W/W} or {X/2}).
+        let half = |part: &str| match unit(part)? {
+            Unit::X | Unit::Snow => Err(invalid()),
+            unit => Ok(unit),
+        };
+        let hybrid = |a: &str, b: &str| match (half(a)?, half(b)?) {
+            (a, b) if a == b => Err(invalid()),
+            halves => Ok(halves),
+        };
+        match parts[..] {
+            [a] => Ok(Self::Single(unit(a)?)),
+            [a, "P"] => Ok(Self::Phyrexian(half(a)?)),
+            [a, b] => hybrid(a, b).map(|(a, b)| Self::Hybrid(a, b)),
+            [a, b, "P"] => hybrid(a, b).map(|(a, b)| Self::PhyrexianHybrid(a, b)),
+            _ => Err(invalid()),
+        }
+    }
+}
+
+impl fmt::Display for Pip {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Single(unit) => write!(f, "{{{unit}}}"),
+            Self::Hybrid(a, b) => write!(f, "{{{a}/{b}}}"),
+            Self::Phyrexian(unit) => write!(f, "{{{unit}/P}}"),
+            Self::PhyrexianHybrid(a, b) => write!(f, "{{{a}/{b}/P}}"),
+        }
+    }
+}
+
+// Without braces, e.g. "2" or "W".
+// Generic amounts are written as plain digits, so e.g. "+2" or "02" are rejected.
+impl FromStr for Unit {
+    type Err = InvalidManaSymbol;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "X" => Ok(Self::X),
+            "W" => Ok(Self::White),
+            "U" => Ok(Self::Blue),
+            "B" => Ok(Self::Black),
+            "R" => Ok(Self::Red),
+            "G" => Ok(Self::Green),
+            "C" => Ok(Self::Colorless),
+            "S" => Ok(Self::Snow),
+            _ if s.bytes().all(|b| b.is_ascii_digit()) && (s == "0" || !s.starts_with('0')) => s
+                .parse()
+                .map(|amount| Self::Generic { amount })
+                .map_err(|_| InvalidManaSymbol(s.into())),
+            _ => Err(InvalidManaSymbol(s.into())),
         }
     }
 }
+
+impl fmt::Display for Unit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Generic { amount } => write!(f, "{amount}"),
+            Self::X => f.write_str("X"),
+            Self::White => f.write_str("W"),
+            Self::Blue => f.write_str("U"),
+            Self::Black => f.write_str("B"),
+            Self::Red => f.write_str("R"),
+            Self::Green => f.write_str("G"),
+            Self::Colorless => f.write_str("C"),
+            Self::Snow => f.write_str("S"),
+        }
+    }
+}
+
+#[test]
+fn test_payment_options() {
+    let cost: ManaCost = "{X}{X}{R}".parse().unwrap();
+    assert_eq!(cost.mana_value(), 1);
+    assert_eq!(cost.x_count(), 2);
+    assert_eq!(cost.mana_value_with_x(3), 7);
+    assert_eq!(cost.mana_value_with_x(200), u8::MAX);
+    assert_eq!(
+        cost.pips[0].payment_options(3),
+        vec![Payment::Generic { amount: 3 }]
+    );
+
+    let two_brid: Pip = "{2/W}".parse().unwrap();
+    assert_eq!(two_brid.mana_value(), 2);
+    assert_eq!(
+        two_brid.payment_options(0),
+        vec![Payment::Generic { amount: 2 }, Payment::Mana(Unit::White)]
+    );
+
+    let phyrexian: Pip = "{B/P}".parse().unwrap();
+    assert_eq!(phyrexian.mana_value(), 1);
+    assert_eq!(
+        phyrexian.payment_options(0),
+        vec![Payment::Mana(Unit::Black), Payment::Life { amount: 2 }]
+    );
+
+    let colorless_hybrid: Pip = "{C/W}".parse().unwrap();
+    assert_eq!(colorless_hybrid.mana_value(), 1);
+    assert_eq!(
+        colorless_hybrid.payment_options(0),
+        vec![Payment::Mana(Unit::Colorless), Payment::Mana(Unit::White)]
+    );
+
+    let snow: Pip = "{S}".parse().unwrap();
+    assert_eq!(snow.mana_value(), 1);
+    assert_eq!(snow.payment_options(0), vec![Payment::Mana(Unit::Snow)]);
+}
+
+#[test]
+fn test_mana_cost_round_trip() {
+    let symbols = [
+        "{0}", "{1}", "{2}", "{16}", "{X}", "{W}", "{U}", "{B}", "{R}", "{G}", "{C}", "{S}",
+        "{W/U}", "{B/G}", "{C/W}", "{2/W}", "{2/B}", "{W/P}", "{R/P}", "{G/U/P}", "{B/R/P}",
+    ];
+    for symbol in symbols {
+        let pip: Pip = symbol.parse().unwrap();
+        assert_eq!(pip.to_string(), symbol);
+    }
+
+    let cost: ManaCost = "{X}{2}{B}{B/G}{2/W}{B/P}{S}{C}".parse().unwrap();
+    assert_eq!(cost.to_string(), "{X}{2}{B}{B/G}{2/W}{B/P}{S}{C}");
+    assert_eq!(cost.mana_value(), 9);
+    assert_eq!(
+        "{2}{B}".parse::<ManaCost>().unwrap().pips,
+        vec![
+            Pip::Single(Unit::Generic { amount: 2 }),
+            Pip::Single(Unit::Black),
+        ]
+    );
+    assert_eq!("".parse::<ManaCost>().unwrap().pips, vec![]);
+    assert_eq!(
+        "{200}{100}".parse::<ManaCost>().unwrap().mana_value(),
+        u8::MAX
+    );
+
+    let invalid_symbols = [
+        "{Q}", "{2}{B", "B", "{W/U/B}", "{}", "{+2}", "{02}", "{W/W}", "{X/2}", "{G/S}", "{X/P}",
+    ];
+    for invalid in invalid_symbols {
+        assert!(invalid.parse::<ManaCost>().is_err(), "{invalid}");
+    }
+}
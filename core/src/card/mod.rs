@@ -1,8 +1,12 @@
-use self::abilities::{Ability, EntersAbility};
+use self::abilities::Ability;
 
 pub mod abilities;
 pub mod color;
+pub mod database;
+pub mod decklist;
 pub mod mana_cost;
+pub mod registry;
+pub mod tokens;
 pub mod types;
 
 #[derive(Debug, Clone)]
@@ -16,46 +20,148 @@ pub enum Card {
     Omen {
         primary: CardFace,
         omen: CardFace,
-    }, // TODO: MDFCs, Transforming cards
+    },
+    // Either face can be played (e.g. a spell with a land on the back).
+    ModalDoubleFaced {
+        front: CardFace,
+        back: CardFace,
+    },
+    // Cast or played with the front face up; the permanent can then transform.
+    // TODO: flip cards
+    Transforming {
+        front: CardFace,
+        back: CardFace,
+    },
+}
+
+// The face(s) of a card being cast as a spell.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CastFace {
+    // Index among `Card::faces` (e.g. the right half of a split card, or an adventure).
+    Face(usize),
+    // Both halves of a split card with fuse, which can only be cast from hand.
+    Fused,
 }
 
 impl Card {
+    // Full name of the card, with faces separated by " // " (e.g. "Cease // Desist").
+    pub fn name(&self) -> String {
+        match self {
+            Self::Single(face) => face.name.clone(),
+            Self::Split(a, b) => format!("{} // {}", a.name, b.name),
+            Self::Adventure { primary, adventure } => {
+                format!("{} // {}", primary.name, adventure.name)
+            }
+            Self::Omen { primary, omen } => format!("{} // {}", primary.name, omen.name),
+            Self::ModalDoubleFaced { front, back } | Self::Transforming { front, back } => {
+                format!("{} // {}", front.name, back.name)
+            }
+        }
+    }
+
+    // All the faces, in order (e.g. left then right, or front then back).
+    pub fn faces(&self) -> Vec<&CardFace> {
+        match self {
+            Self::Single(face) => vec![face],
+            Self::Split(left, right) => vec![left, right],
+            Self::Adventure { primary, adventure } => vec![primary, adventure],
+            Self::Omen { primary, omen } => vec![primary, omen],
+            Self::ModalDoubleFaced { front, back } | Self::Transforming { front, back } => {
+                vec![front, back]
+            }
+        }
+    }
+
+    // The faces of the spell while it is on the stack.
+    pub fn cast_faces(&self, face: CastFace) -> Vec<&CardFace> {
+        match (self, face) {
+            (_, CastFace::Face(index)) => self.faces().get(index).into_iter().copied().collect(),
+            (Self::Split(left, right), CastFace::Fused) => vec![left, right],
+            (_, CastFace::Fused) => Vec::new(),
+        }
+    }
+
+    pub fn can_fuse(&self) -> bool {
+        match self {
+            Self::Split(left, right) => [left, right]
+                .iter()
+                .any(|f| f.abilities.iter().any(|a| matches!(a, Ability::Fuse))),
+            _ => false,
+        }
+    }
+
     pub fn primary_name(&self) -> Option<&str> {
         match self {
             Self::Single(face) => Some(&face.name),
             Self::Adventure { primary, .. } => Some(&primary.name),
             Self::Omen { primary, .. } => Some(&primary.name),
+            Self::ModalDoubleFaced { front, .. } | Self::Transforming { front, .. } => {
+                Some(&front.name)
+            }
             Self::Split(_, _) => None,
         }
     }
 
+    // The mana value of the card outside the stack, where a split card has the combined
+    // mana value of its halves (see `cast_mana_value` for spells).
     pub fn mana_value(&self) -> u8 {
         match self {
             Self::Single(face) => face.mana_value(),
-            Self::Split(a, b) => a.mana_value() + b.mana_value(),
+            Self::Split(a, b) => a.mana_value().saturating_add(b.mana_value()),
             Self::Adventure { primary, .. } => primary.mana_value(),
             Self::Omen { primary, .. } => primary.mana_value(),
+            // Outside the stack and battlefield a double-faced card has only its front face.
+            // (The back face of a transformed permanent also uses the front's mana value.)
+            Self::ModalDoubleFaced { front, .. } | Self::Transforming { front, .. } => {
+                front.mana_value()
+            }
         }
     }
 
+    // Only the faces being cast count while the card is a spell on the stack,
+    // where X has the value chosen as it was cast.
+    pub fn cast_mana_value(&self, face: CastFace, x: u8) -> u8 {
+        self.cast_faces(face)
+            .iter()
+            .filter_map(|f| f.mana_cost.as_ref())
+            .fold(0, |total, cost| {
+                total.saturating_add(cost.mana_value_with_x(x))
+            })
+    }
+
     pub fn is_land(&self) -> bool {
         match &self {
             Self::Single(face) => face.is_land(),
-            Self::Adventure { primary, .. } => primary.is_land(),
-            Self::Split(_, _) | Self::Omen { .. } => false,
+            Self::Adventure { primary, .. } | Self::Omen { primary, .. } => primary.is_land(),
+            Self::ModalDoubleFaced { front, .. } | Self::Transforming { front, .. } => {
+                front.is_land()
+            }
+            Self::Split(_, _) => false,
+        }
+    }
+
+    // Indices of the faces which can be played as a land
+    // (e.g. the back of a modal double-faced card, even though the card is not a land).
+    pub fn land_faces(&self) -> Vec<usize> {
+        match self {
+            Self::ModalDoubleFaced { front, back } => [front, back]
+                .into_iter()
+                .enumerate()
+                .filter(|(_, face)| face.is_land())
+                .map(|(index, _)| index)
+                .collect(),
+            _ if self.is_land() => vec![0],
+            _ => Vec::new(),
         }
     }
 
     pub fn enters_tapped(&self) -> bool {
         match self {
-            Card::Single(face) => face
-                .abilities
-                .iter()
-                .any(|a| matches!(a, Ability::Enters(EntersAbility::Tapped))),
-            Card::Adventure { primary, .. } | Card::Omen { primary, .. } => primary
-                .abilities
-                .iter()
-                .any(|a| matches!(a, Ability::Enters(EntersAbility::Tapped))),
+            Card::Single(face) => face.enters_tapped(),
+            Card::Adventure { primary, .. } | Card::Omen { primary, .. } => primary.enters_tapped(),
+            Card::ModalDoubleFaced { front, .. } | Card::Transforming { front, .. } => {
+                front.enters_tapped()
+            }
             Card::Split(_, _) => false,
         }
     }
@@ -67,6 +173,8 @@ pub struct CardFace {
     pub mana_cost: Option<mana_cost::ManaCost>,
     pub color: color::Color,
     pub is_legendary: bool,
+    // Mana produced by a snow permanent can pay for {S}.
+    pub is_snow: bool,
     pub type_line: types::Type,
     pub abilities: Vec<abilities::Ability>,
 }
@@ -82,4 +190,45 @@ impl CardFace {
     pub fn is_land(&self) -> bool {
         matches!(self.type_line, types::Type::Land(_))
     }
+
+    pub fn is_instant_or_sorcery(&self) -> bool {
+        matches!(
+            self.type_line.without_kindred(),
+            types::Type::Instant | types::Type::Sorcery
+        )
+    }
+
+    pub fn enters_tapped(&self) -> bool {
+        self.abilities
+            .iter()
+            .any(|a| matches!(a, Ability::EntersTapped))
+    }
+
+    pub fn is_creature(&self) -> bool {
+        matches!(
+            self.type_line,
+            types::Type::Creature(_)
+                | types::Type::ArtifactCreature { .. }
+                | types::Type::EnchantmentCreature { .. }
+        )
+    }
+}
+
+// A colorless face without a mana cost, for building cards in tests.
+#[cfg(test)]
+pub(crate) fn test_face(name: &str, type_line: types::Type, abilities: Vec<Ability>) -> CardFace {
+    CardFace {
+        name: name.into(),
+        mana_cost: None,
+        color: color::Color::colorless(),
+        is_legendary: false,
+        is_snow: false,
+        type_line,
+        abilities,
+    }
+}
+
+#[cfg(test)]
+pub(crate) fn test_card(name: &str, type_line: types::Type, abilities: Vec<Ability>) -> Card {
+    Card::Single(test_face(name, type_line, abilities))
 }
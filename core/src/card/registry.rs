@@ -0,0 +1,39 @@
+use {crate::card::Card, std::collections::HashMap};
+
+/// Card definitions that can be looked up by name (e.g. when importing a deck list).
+#[derive(Debug, Clone, Default)]
+pub struct CardRegistry {
+    cards: HashMap<String, Card>,
+}
+
+impl CardRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // The card can be found by its full name (e.g. "Cease // Desist")
+    // or the name of its first face (e.g. "Cease").
+    pub fn insert(&mut self, card: Card) {
+        let first_face = match &card {
+            Card::Single(face) | Card::Split(face, _) => &face.name,
+            Card::Adventure { primary, .. } | Card::Omen { primary, .. } => &primary.name,
+            Card::ModalDoubleFaced { front, .. } | Card::Transforming { front, .. } => &front.name,
+        };
+        self.cards.insert(first_face.clone(), card.clone());
+        self.cards.insert(card.name(), card);
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Card> {
+        self.cards.get(name)
+    }
+}
+
+impl FromIterator<Card> for CardRegistry {
+    fn from_iter<T: IntoIterator<Item = Card>>(iter: T) -> Self {
+        let mut registry = Self::new();
+        for card in iter {
+            registry.insert(card);
+        }
+        registry
+    }
+}
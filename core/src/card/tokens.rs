@@ -0,0 +1,195 @@
+use crate::card::{
+    CardFace,
+    abilities::{Ability, AbilityCost, ActivatedAbility, Effect, ManaAbility, ManaProduction},
+    color::Color,
+    types::{
+        ArtifactProperties, ArtifactSubtypes, CreatureProperties, CreatureSubtypes, Power,
+        Toughness, Type,
+    },
+};
+
+// Definitions of tokens to create with `Battlefield::create_token`.
+
+fn artifact(subtype: ArtifactSubtypes, abilities: Vec<Ability>) -> CardFace {
+    CardFace {
+        name: format!("{subtype:?}"),
+        mana_cost: None,
+        color: Color::colorless(),
+        is_legendary: false,
+        is_snow: false,
+        type_line: Type::Artifact(ArtifactProperties {
+            subtypes: vec![subtype],
+        }),
+        abilities,
+    }
+}
+
+fn cost(mana_cost: &str, tap: bool, discard: u8) -> AbilityCost {
+    AbilityCost {
+        tap,
+        mana_cost: (!mana_cost.is_empty()).then(|| mana_cost.parse().unwrap()),
+        sacrifice: true,
+        discard,
+        loyalty: None,
+    }
+}
+
+// "{2}, {T}, Sacrifice this artifact: You gain 3 life."
+pub fn food() -> CardFace {
+    let ability = ActivatedAbility {
+        cost: cost("{2}", true, 0),
+        effect: Effect::GainLife { amount: 3 },
+        sorcery_speed: false,
+    };
+    artifact(ArtifactSubtypes::Food, vec![Ability::Activated(ability)])
+}
+
+// "{T}, Sacrifice this artifact: Add one mana of any color."
+pub fn treasure() -> CardFace {
+    let ability = ManaAbility {
+        cost: cost("", true, 0),
+        ..ManaAbility::tap(ManaProduction::any_color())
+    };
+    artifact(ArtifactSubtypes::Treasure, vec![Ability::Mana(ability)])
+}
+
+// "{2}, Sacrifice this artifact: Draw a card."
+pub fn clue() -> CardFace {
+    let ability = ActivatedAbility {
+        cost: cost("{2}", false, 0),
+        effect: Effect::Draw { amount: 1 },
+        sorcery_speed: false,
+    };
+    artifact(ArtifactSubtypes::Clue, vec![Ability::Activated(ability)])
+}
+
+// "{1}, {T}, Discard a card, Sacrifice this artifact: Draw a card."
+pub fn blood() -> CardFace {
+    let ability = ActivatedAbility {
+        cost: cost("{1}", true, 1),
+        effect: Effect::Draw { amount: 1 },
+        sorcery_speed: false,
+    };
+    artifact(ArtifactSubtypes::Blood, vec![Ability::Activated(ability)])
+}
+
+// "{1}, {T}, Sacrifice this artifact: Target creature you control explores.
+// Activate only as a sorcery."
+pub fn map() -> CardFace {
+    let ability = ActivatedAbility {
+        cost: cost("{1}", true, 0),
+        effect: Effect::Explore,
+        sorcery_speed: true,
+    };
+    artifact(ArtifactSubtypes::Map, vec![Ability::Activated(ability)])
+}
+
+// E.g. a 1/1 white Human creature token.
+pub fn creature(
+    name: &str,
+    color: Color,
+    subtypes: Vec<CreatureSubtypes>,
+    power: u32,
+    toughness: u32,
+    abilities: Vec<Ability>,
+) -> CardFace {
+    CardFace {
+        name: name.into(),
+        mana_cost: None,
+        color,
+        is_legendary: false,
+        is_snow: false,
+        type_line: Type::Creature(CreatureProperties {
+            subtypes,
+            power: Power::Value(power),
+            toughness: Toughness::Value(toughness),
+        }),
+        abilities,
+    }
+}
+
+#[test]
+fn test_tokens() {
+    use crate::{
+        card::{Card, mana_cost::Unit},
+        game_play::{
+            agent::{Action, Agent, Goldfish},
+            battlefield::{Battlefield, Event},
+            counters::CounterKind,
+            player::{PlayerId, PlayerState},
+            turn::Step,
+        },
+        mana::{payment::PaymentPlan, pool::Mana},
+    };
+
+    // Activates the first ability of a token with the name, if there is one.
+    struct ActivateAll(&'static str);
+    impl Agent for ActivateAll {
+        fn choose_action(&mut self, battlefield: &Battlefield, _: PlayerId) -> Action {
+            let token = battlefield
+                .objects
+                .iter()
+                .find(|o| o.card.card.name() == self.0 && o.activated_abilities().next().is_some());
+            match token {
+                Some(token) => Action::ActivateAbility {
+                    source: token.id(),
+                    ability: 0,
+                    payment: PaymentPlan::default(),
+                },
+                None => Action::Pass,
+            }
+        }
+    }
+
+    let deck = vec![Card::Single(treasure()); 3];
+    let mut battlefield = Battlefield::new(vec![PlayerState::new(0, deck)]);
+    let player = battlefield.players[0].id;
+    battlefield.turn.step = Step::PrecombatMain;
+
+    // The treasure is sacrificed for mana, and ceases to exist.
+    let id = battlefield.create_token(player, treasure());
+    battlefield.objects[0].summoning_sick = false;
+    battlefield
+        .activate_mana_ability(id, 0, &[Unit::Blue])
+        .unwrap();
+    assert!(battlefield.objects.is_empty());
+    assert_eq!(battlefield.players[0].zones.graveyard.len(), 1);
+    battlefield.check_state_based_actions(&mut Goldfish);
+    assert!(battlefield.players[0].zones.graveyard.is_empty());
+
+    // Tapping for mana and then paying for the clue from the pool.
+    battlefield.create_token(player, clue());
+    for _ in 0..2 {
+        let id = battlefield.create_token(player, treasure());
+        battlefield.object_mut(id).unwrap().summoning_sick = false;
+        battlefield
+            .activate_mana_ability(id, 0, &[Unit::Red])
+            .unwrap();
+    }
+    battlefield.play_priority(&mut ActivateAll("Clue"));
+    assert!(battlefield.objects.is_empty());
+    assert_eq!(battlefield.players[0].zones.hand.len(), 1);
+
+    // The map can only be activated at sorcery speed, and the creature explores.
+    let bear = creature("Bear", Color::green(), Vec::new(), 2, 2, Vec::new());
+    let bear = battlefield.create_token(player, bear);
+    let map = battlefield.create_token(player, map());
+    battlefield.object_mut(map).unwrap().summoning_sick = false;
+    let pool = &mut battlefield.players[0].mana_pool;
+    pool.add(Mana {
+        unit: Unit::Green,
+        restriction: None,
+        snow: false,
+    });
+    battlefield.turn.step = Step::BeginningOfCombat;
+    battlefield.play_priority(&mut ActivateAll("Map"));
+    assert!(battlefield.object(map).is_some());
+    battlefield.turn.step = Step::PrecombatMain;
+    battlefield.play_priority(&mut ActivateAll("Map"));
+    assert!(battlefield.object(map).is_none());
+    let explored = |e: &Event| matches!(e, Event::Explored(id) if *id == bear);
+    assert!(battlefield.log.iter().any(explored));
+    let bear = battlefield.object(bear).unwrap();
+    assert_eq!(bear.count_counters(&CounterKind::PlusOnePlusOne), 1);
+    assert_eq!(bear.power(), 3);
+}
@@ -16,7 +16,39 @@ pub enum Type {
     Instant,
     // TODO: sorcery properties
     Sorcery,
-    // TODO: Planeswalker, Battle, Kindred
+    Planeswalker(PlaneswalkerProperties),
+    Battle(BattleProperties),
+    // A non-creature card with creature subtypes (e.g. "Kindred Instant — Faerie").
+    Kindred {
+        subtypes: Vec<CreatureSubtypes>,
+        card_type: Box<Type>,
+    },
+}
+
+impl Type {
+    pub fn creature(&self) -> Option<&CreatureProperties> {
+        match self {
+            Self::Creature(creature)
+            | Self::ArtifactCreature { creature, .. }
+            | Self::EnchantmentCreature { creature, .. } => Some(creature),
+            _ => None,
+        }
+    }
+
+    // The card type of a kindred card (e.g. `Instant` for a Kindred Instant).
+    pub fn without_kindred(&self) -> &Type {
+        match self {
+            Self::Kindred { card_type, .. } => card_type,
+            _ => self,
+        }
+    }
+
+    pub fn creature_subtypes(&self) -> &[CreatureSubtypes] {
+        match self {
+            Self::Kindred { subtypes, .. } => subtypes,
+            _ => self.creature().map_or(&[], |c| &c.subtypes),
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -30,6 +62,27 @@ pub struct EnchantmentProperties {
     pub subtypes: Vec<EnchantmentSubtypes>,
 }
 
+// TODO: planeswalker subtypes
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlaneswalkerProperties {
+    // The number of loyalty counters it enters with.
+    pub loyalty: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BattleProperties {
+    pub subtypes: Vec<BattleSubtypes>,
+    // The number of defense counters it enters with.
+    pub defense: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BattleSubtypes {
+    // Protected by an opponent of its controller, chosen as it enters.
+    // TODO: cast transformed when defeated
+    Siege,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct ArtifactProperties {
     pub subtypes: Vec<ArtifactSubtypes>,
@@ -58,6 +111,10 @@ pub enum EnchantmentSubtypes {
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum ArtifactSubtypes {
     Food,
+    Treasure,
+    Clue,
+    Blood,
+    Map,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -0,0 +1,217 @@
+use crate::{
+    card::{CastFace, abilities::TargetSpec, mana_cost::Unit},
+    game_play::{
+        ObjectId, OwnedCard, battlefield::Battlefield, combat::Defender, player::PlayerId,
+        target::Target,
+    },
+    mana::payment::PaymentPlan,
+};
+
+/// Makes the decisions for a player when the engine needs them
+/// (e.g. whether to mulligan, what to do with priority, how to resolve a scry trigger).
+/// The default implementations make the simplest legal choice.
+/// Different strategies can be compared by running the same game with different agents.
+pub trait Agent {
+    // Whether to keep the opening hand (currently in the player's hand)
+    // after taking `mulligans` mulligans so far.
+    fn keep_hand(
+        &mut self,
+        _battlefield: &Battlefield,
+        _player: PlayerId,
+        _mulligans: usize,
+    ) -> bool {
+        true
+    }
+
+    // After keeping a hand, choose `amount` distinct cards in hand to put
+    // on the bottom of the library (in that order).
+    fn choose_cards_to_bottom(
+        &mut self,
+        battlefield: &Battlefield,
+        player: PlayerId,
+        amount: usize,
+    ) -> Vec<ObjectId> {
+        let hand = &battlefield.player(player).zones.hand;
+        hand.iter().take(amount).map(|c| c.id).collect()
+    }
+
+    // The land in hand to play, if any.
+    fn choose_land(&mut self, battlefield: &Battlefield, player: PlayerId) -> Option<ObjectId> {
+        battlefield
+            .player(player)
+            .zones
+            .hand
+            .iter()
+            .find(|c| !c.card.land_faces().is_empty())
+            .map(|c| c.id)
+    }
+
+    // Which face of the land to play (e.g. either side of a modal double-faced card).
+    // `faces` is never empty.
+    fn choose_land_face(
+        &mut self,
+        _battlefield: &Battlefield,
+        _player: PlayerId,
+        _card: &OwnedCard,
+        faces: &[usize],
+    ) -> usize {
+        faces[0]
+    }
+
+    // What to do while holding priority.
+    fn choose_action(&mut self, battlefield: &Battlefield, player: PlayerId) -> Action {
+        if battlefield.can_play_land(player)
+            && let Some(index) = self.choose_land(battlefield, player)
+        {
+            Action::PlayLand(index)
+        } else {
+            Action::Pass
+        }
+    }
+
+    // Choose a target for `spec` from the legal `candidates`, which is never empty.
+    fn choose_target(
+        &mut self,
+        _battlefield: &Battlefield,
+        _player: PlayerId,
+        _spec: TargetSpec,
+        candidates: &[Target],
+    ) -> Target {
+        candidates[0]
+    }
+
+    // Creatures to attack with, and the player, planeswalker or battle each attacks.
+    fn choose_attackers(
+        &mut self,
+        _battlefield: &Battlefield,
+        _player: PlayerId,
+    ) -> Vec<(ObjectId, Defender)> {
+        Vec::new()
+    }
+
+    // The opponent who protects a Siege entering under the player's control.
+    // `opponents` is never empty.
+    fn choose_protector(
+        &mut self,
+        _battlefield: &Battlefield,
+        _player: PlayerId,
+        opponents: &[PlayerId],
+    ) -> PlayerId {
+        opponents[0]
+    }
+
+    // Pairs of (blocking creature, attacking creature).
+    // An attacker blocked by several creatures assigns damage to them in the order given.
+    fn choose_blockers(
+        &mut self,
+        _battlefield: &Battlefield,
+        _player: PlayerId,
+    ) -> Vec<(ObjectId, ObjectId)> {
+        Vec::new()
+    }
+
+    // The legend rule: of the legendary permanents with the same name,
+    // choose the one to keep. The rest go to the graveyard.
+    fn choose_legend_to_keep(
+        &mut self,
+        _battlefield: &Battlefield,
+        _player: PlayerId,
+        candidates: &[ObjectId],
+    ) -> ObjectId {
+        candidates[0]
+    }
+
+    // Choose `amount` distinct cards in hand to discard.
+    fn choose_cards_to_discard(
+        &mut self,
+        battlefield: &Battlefield,
+        player: PlayerId,
+        amount: usize,
+    ) -> Vec<ObjectId> {
+        let hand = &battlefield.player(player).zones.hand;
+        hand.iter().take(amount).map(|c| c.id).collect()
+    }
+
+    // Whether to put the nonland card revealed by exploring into the graveyard
+    // (otherwise it stays on top of the library).
+    fn choose_explore_to_graveyard(
+        &mut self,
+        _battlefield: &Battlefield,
+        _player: PlayerId,
+        _card: &OwnedCard,
+    ) -> bool {
+        false
+    }
+
+    // One choice per card, in the order the cards were on top of the library.
+    fn choose_scry(
+        &mut self,
+        _battlefield: &Battlefield,
+        _player: PlayerId,
+        cards: &[OwnedCard],
+    ) -> Vec<ScryChoice> {
+        vec![ScryChoice::Top; cards.len()]
+    }
+
+    // One choice per card, in the order the cards were on top of the library.
+    fn choose_surveil(
+        &mut self,
+        _battlefield: &Battlefield,
+        _player: PlayerId,
+        cards: &[OwnedCard],
+    ) -> Vec<SurveilChoice> {
+        vec![SurveilChoice::Top; cards.len()]
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Action {
+    Pass,
+    // A land in hand
+    PlayLand(ObjectId),
+    // A card in hand (or on an adventure in exile), the face to cast it as,
+    // the value of X (0 for a spell without {X}), and how to pay for it (see `find_payment`).
+    CastSpell {
+        card: ObjectId,
+        face: CastFace,
+        x: u8,
+        payment: PaymentPlan,
+    },
+    // Index among the activated abilities of the permanent, and how to pay for it.
+    ActivateAbility {
+        source: ObjectId,
+        ability: usize,
+        payment: PaymentPlan,
+    },
+    // Pays the cost of a locked door of a Room (see `Battlefield::unlock_door`).
+    UnlockDoor {
+        room: ObjectId,
+        door: usize,
+        payment: PaymentPlan,
+    },
+    // Adds mana to the pool (see `Battlefield::activate_mana_ability`).
+    ActivateManaAbility {
+        source: ObjectId,
+        ability: usize,
+        produce: Vec<Unit>,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScryChoice {
+    Top,
+    Bottom,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SurveilChoice {
+    Top,
+    Graveyard,
+}
+
+/// An agent which always makes the default choice.
+/// Useful for opponents who do nothing (and tests).
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Goldfish;
+
+impl Agent for Goldfish {}
@@ -1,12 +1,27 @@
 use crate::{
     card::{
-        Card,
-        abilities::{Ability, EntersAbility},
+        Card, CardFace, CastFace,
+        abilities::{
+            Ability, ActivatedAbility, Effect, ManaAbility, PermanentFilter, TargetSpec,
+            TriggerCondition,
+        },
+        mana_cost::{ManaCost, Unit},
+        types::{BattleSubtypes, Type},
     },
     game_play::{
-        OwnedCard,
-        counters::Counters,
-        player::{PlayerId, PlayerState},
+        ObjectId, OwnedCard,
+        agent::{Action, Agent, ScryChoice, SurveilChoice},
+        combat::Combat,
+        counters::{self, CounterKind, Counters},
+        layers::{Characteristics, ContinuousEffect, Timestamp},
+        player::{Decked, PlayerId, PlayerState},
+        stack::{Priority, PriorityOutcome, Stack, StackObject, StackObjectKind},
+        target::{NoLegalTargets, Resolution, Target},
+        turn::{Step, Turn},
+    },
+    mana::{
+        payment::PaymentPlan,
+        pool::{CannotPay, Mana},
     },
 };
 
@@ -14,122 +29,1416 @@ use crate::{
 pub struct Battlefield {
     pub players: Vec<PlayerState>,
     pub objects: Vec<InPlayObject>,
+    pub stack: Stack,
+    // Abilities which triggered, but are not yet on the stack.
+    // They are put on the stack the next time a player would receive priority.
+    pub pending_triggers: Vec<StackObject>,
+    pub turn: Turn,
+    pub priority: Priority,
+    pub combat: Combat,
+    // Effects from resolved spells and abilities (see `apply_continuous_effects`).
+    pub continuous_effects: Vec<ContinuousEffect>,
     // Log of events that took place during the game
     pub log: Vec<Event>,
 }
 
 impl Battlefield {
-    pub fn play_land(&mut self, card: OwnedCard) -> Option<EntersAbility> {
-        let tapped = card.card.enters_tapped();
-        // TODO: there are other kinds of ETBs for lands.
-        // TODO: there can be more than 1 ETB on a land.
-        let etb = match &card.card {
-            Card::Single(face) => face.abilities.iter().find_map(|a| {
-                if let Ability::Enters(etb) = a {
-                    if matches!(
-                        etb,
-                        EntersAbility::Scry { .. } | EntersAbility::Surveil { .. }
-                    ) {
-                        Some(etb.clone())
-                    } else {
-                        None
-                    }
-                } else {
-                    None
-                }
-            }),
-            _ => None,
+    // The first player takes the first turn.
+    pub fn new(players: Vec<PlayerState>) -> Self {
+        let active_player = players.first().expect("There is a player").id;
+        Self {
+            players,
+            objects: Vec::new(),
+            stack: Stack::default(),
+            pending_triggers: Vec::new(),
+            turn: Turn {
+                number: 0,
+                active_player,
+                step: Step::Untap,
+                lands_played: 0,
+                loyalty_activated: Vec::new(),
+            },
+            priority: Priority::new(active_player),
+            combat: Combat::default(),
+            continuous_effects: Vec::new(),
+            log: Vec::new(),
+        }
+    }
+
+    // Draw seven cards, taking mulligans until the `agent` keeps the hand
+    // (London mulligan: one card is put on the bottom for each mulligan taken).
+    pub fn draw_opening_hand(&mut self, player: PlayerId, agent: &mut dyn Agent) {
+        let mut mulligans = 0;
+        loop {
+            for card in self.take_from_library(player, 7) {
+                let card = card.change_zone();
+                self.log_event(Event::Draw(card.id));
+                self.player_mut(player).zones.hand.push(card);
+            }
+            if agent.keep_hand(self, player, mulligans) {
+                break;
+            }
+            self.log_event(Event::Mulligan(player));
+            self.player_mut(player).shuffle_hand_into_library();
+            mulligans += 1;
+        }
+
+        if mulligans == 0 {
+            return;
+        }
+        let cards = agent.choose_cards_to_bottom(self, player, mulligans);
+        let state = self.player_mut(player);
+        for card in cards {
+            if let Some(card) = state.take_from_hand(card) {
+                state.zones.library.push_back(card.change_zone());
+            }
+        }
+    }
+
+    // Record an event in the log and collect any abilities that trigger from it.
+    pub fn log_event(&mut self, event: Event) {
+        self.collect_triggers(&event);
+        self.log.push(event);
+    }
+
+    // Begin a new turn for the given player, performing the untap step.
+    // Usually this is `next_player(turn.active_player)`, but a simulation
+    // may choose to skip the turns of a goldfish opponent.
+    pub fn start_turn(&mut self, player: PlayerId) {
+        self.turn = Turn {
+            number: self.turn.number + 1,
+            active_player: player,
+            step: Step::Untap,
+            lands_played: 0,
+            loyalty_activated: Vec::new(),
         };
-        let object = InPlayObject {
-            controller: card.owner,
-            is_token: false,
-            counters: Vec::new(),
-            card,
-            tapped,
+        self.priority = Priority::new(player);
+        self.combat = Combat::default();
+        self.log_event(Event::StartTurn(player));
+
+        let mut untapped = Vec::new();
+        let mut stunned = Vec::new();
+        for object in self.objects.iter_mut().filter(|o| o.controller == player) {
+            object.summoning_sick = false;
+            if object.tapped && object.count_counters(&CounterKind::Stun) > 0 {
+                stunned.push(object.id());
+            } else if object.tapped {
+                object.tapped = false;
+                untapped.push(Event::Untap(object.id()));
+            }
+        }
+        for event in untapped {
+            self.log_event(event);
+        }
+        for id in stunned {
+            self.remove_counters(Target::Permanent(id), CounterKind::Stun, 1);
+        }
+    }
+
+    // Move to the next step of the turn, performing its turn-based actions
+    // and putting "at the beginning of" triggers on the stack.
+    // Returns `None` once the turn is over (i.e. after the cleanup step).
+    pub fn advance_step(&mut self, agent: &mut dyn Agent) -> Option<Step> {
+        // Mana empties from the pools at the end of each step.
+        for player in self.players.iter_mut() {
+            player.mana_pool.empty();
+        }
+        let mut next = self.turn.step.next();
+        while let Some(step) = next
+            && self.skips_step(step)
+        {
+            next = step.next();
+        }
+        let step = next?;
+        self.turn.step = step;
+        self.priority = Priority::new(self.turn.active_player);
+        self.log_event(Event::BeginStep(step));
+
+        let active_player = self.turn.active_player;
+        match step {
+            // The player who goes first skips the draw of their first turn,
+            // i.e. the first turn of the game.
+            Step::Draw if self.turn.number == 1 => (),
+            Step::Draw => {
+                // A player who cannot draw loses the next time state-based actions are checked.
+                self.draw_card(active_player).ok();
+            }
+            Step::DeclareAttackers => self.declare_attackers(agent),
+            Step::DeclareBlockers => self.declare_blockers(agent),
+            Step::FirstStrikeDamage => self.combat_damage(true),
+            Step::CombatDamage => self.combat_damage(false),
+            // Creatures are removed from combat as the end of combat step ends.
+            Step::PostcombatMain => self.combat = Combat::default(),
+            Step::Cleanup => {
+                // TODO: discard down to maximum hand size.
+                for object in self.objects.iter_mut() {
+                    object.damage = 0;
+                }
+                self.end_turn_effects();
+                self.log_event(Event::EndTurn(active_player));
+            }
+            _ => (),
+        }
+        self.put_triggers_on_stack(agent);
+
+        Some(step)
+    }
+
+    pub fn next_player(&self, player: PlayerId) -> PlayerId {
+        let index = self
+            .players
+            .iter()
+            .position(|p| p.id == player)
+            .expect("Player is in the game");
+        self.players[(index + 1) % self.players.len()].id
+    }
+
+    pub fn draw_card(&mut self, player: PlayerId) -> Result<(), Decked> {
+        let player = self.player_mut(player);
+        player.draw_a_card()?;
+        let card = player.zones.hand.last().expect("A card was just drawn").id;
+        self.log_event(Event::Draw(card));
+        Ok(())
+    }
+
+    // Puts the card into its owner's hand as a new object, without it being drawn.
+    pub fn put_into_hand(&mut self, card: OwnedCard) {
+        let card = card.change_zone();
+        let id = card.id;
+        self.player_mut(card.owner).zones.hand.push(card);
+        self.log_event(Event::PutIntoHand(id));
+    }
+
+    pub fn play_land(&mut self, card: OwnedCard, agent: &mut dyn Agent) {
+        self.turn.lands_played += 1;
+        let face = match card.card.land_faces().as_slice() {
+            [] => 0,
+            [face] => *face,
+            faces => agent.choose_land_face(self, card.owner, &card, faces),
         };
-        self.log.push(Event::EnteredPlay(object.clone()));
+        let object = InPlayObject::with_face(card, face);
+        let id = object.id();
         self.objects.push(object);
-        // TODO: technically at this point the ETB ability would go on the stack (if any).
-        etb
+        self.apply_continuous_effects();
+        self.log_event(Event::EnteredPlay(id));
+        self.put_triggers_on_stack(agent);
+    }
+
+    // Creates a token (see `card::tokens`) under the control of `controller`, who owns it.
+    pub fn create_token(&mut self, controller: PlayerId, token: CardFace) -> ObjectId {
+        let object = InPlayObject::new(OwnedCard::token(Card::Single(token), controller));
+        let id = object.id();
+        self.objects.push(object);
+        self.apply_continuous_effects();
+        self.log_event(Event::EnteredPlay(id));
+        id
+    }
+
+    // Targets of the spell are chosen by the `agent`.
+    // If the spell requires a target, but there are none, then it cannot be cast
+    // and the card is returned.
+    // The spell is cast without paying its mana cost, so X is 0.
+    pub fn cast_spell(
+        &mut self,
+        card: OwnedCard,
+        face: CastFace,
+        agent: &mut dyn Agent,
+    ) -> Result<(), NoLegalTargets> {
+        let controller = card.owner; // TODO: depends on caster, not owner
+        let Some(targets) = self.choose_spell_targets(controller, &card.card, face, agent) else {
+            return Err(NoLegalTargets(Box::new(card)));
+        };
+        self.put_spell_on_stack(controller, card, face, 0, targets, agent);
+        Ok(())
+    }
+
+    fn choose_spell_targets(
+        &self,
+        controller: PlayerId,
+        card: &Card,
+        face: CastFace,
+        agent: &mut dyn Agent,
+    ) -> Option<Vec<Target>> {
+        let specs: Vec<TargetSpec> = spell_effects(card, face)
+            .into_iter()
+            .flat_map(|e| e.targets().iter().copied())
+            .collect();
+        self.choose_targets(controller, &specs, agent)
     }
 
-    pub fn cast_spell(&mut self, card: OwnedCard) {
-        // TODO: spell should go on the stack
-        // TODO: need to specify which half of a split card is being cast
-        // TODO: players can have choices within the ETB. For example discover allows to hand.
+    fn put_spell_on_stack(
+        &mut self,
+        controller: PlayerId,
+        card: OwnedCard,
+        face: CastFace,
+        x: u8,
+        targets: Vec<Target>,
+        agent: &mut dyn Agent,
+    ) {
+        let card = card.change_zone();
+        let id = card.id;
+        self.stack.push(StackObject {
+            controller,
+            kind: StackObjectKind::Spell {
+                card: Box::new(card),
+                face,
+                x,
+            },
+            targets,
+            source: None,
+        });
+        self.log_event(Event::Cast(id));
+        self.put_triggers_on_stack(agent);
+        // The player who cast the spell receives priority again.
+        self.priority = Priority::new(controller);
+    }
+
+    // Returns `None` if there is no legal choice for one of the targets.
+    // The same target is not chosen twice, as in "two target players".
+    fn choose_targets(
+        &self,
+        controller: PlayerId,
+        specs: &[TargetSpec],
+        agent: &mut dyn Agent,
+    ) -> Option<Vec<Target>> {
+        let mut targets = Vec::with_capacity(specs.len());
+        for spec in specs {
+            let candidates: Vec<Target> = self
+                .legal_targets(controller, *spec)
+                .into_iter()
+                .filter(|t| !targets.contains(t))
+                .collect();
+            if candidates.is_empty() {
+                return None;
+            }
+            let target = agent.choose_target(self, controller, *spec, &candidates);
+            debug_assert!(candidates.contains(&target), "Target must be legal");
+            targets.push(target);
+        }
+        Some(targets)
+    }
+
+    // Targets which are no longer legal are replaced by `None`.
+    fn check_targets(
+        &self,
+        controller: PlayerId,
+        specs: &[TargetSpec],
+        targets: Vec<Target>,
+    ) -> Vec<Option<Target>> {
+        specs
+            .iter()
+            .zip(targets)
+            .map(|(spec, target)| {
+                self.is_legal_target(controller, *spec, target)
+                    .then_some(target)
+            })
+            .collect()
+    }
+
+    // A land can be played by the active player during their main phase
+    // while the stack is empty, once per turn.
+    pub fn can_play_land(&self, player: PlayerId) -> bool {
+        self.turn.lands_played == 0 && self.has_sorcery_timing(player)
+    }
+
+    // Instants can be cast whenever the player has priority,
+    // other spells only at sorcery speed.
+    pub fn can_cast(&self, player: PlayerId, card: &Card, face: CastFace) -> bool {
+        let can_be_cast = match face {
+            CastFace::Face(index) => spell_faces(card).contains(&index),
+            CastFace::Fused => card.can_fuse(),
+        };
+        if !can_be_cast {
+            return false;
+        }
+        let is_instant =
+            (card.cast_faces(face).iter()).all(|f| *f.type_line.without_kindred() == Type::Instant);
+        if is_instant {
+            self.priority.holder == player
+        } else {
+            self.has_sorcery_timing(player)
+        }
+    }
 
-        // The only non-permanent spell we current cast is Cease.
-        if let Card::Split(cease, _) = &card.card
-            && cease.name == "Cease"
+    fn has_sorcery_timing(&self, player: PlayerId) -> bool {
+        self.turn.active_player == player
+            && self.priority.holder == player
+            && self.turn.step.is_main_phase()
+            && self.stack.is_empty()
+    }
+
+    pub fn object(&self, id: ObjectId) -> Option<&InPlayObject> {
+        self.objects.iter().find(|o| o.id() == id)
+    }
+
+    pub fn object_mut(&mut self, id: ObjectId) -> Option<&mut InPlayObject> {
+        self.objects.iter_mut().find(|o| o.id() == id)
+    }
+
+    // Does nothing if the permanent is no longer on the battlefield.
+    pub fn tap(&mut self, id: ObjectId) {
+        if let Some(object) = self.object_mut(id) {
+            object.tapped = true;
+            self.log_event(Event::Tap(id));
+        }
+    }
+
+    // Activates the mana ability (index among the mana abilities of the permanent `source`),
+    // adding `produce` (which must be one of the possible outputs) to the controller's pool.
+    // Mana abilities do not use the stack.
+    pub fn activate_mana_ability(
+        &mut self,
+        source: ObjectId,
+        ability: usize,
+        produce: &[Unit],
+    ) -> Result<(), CannotPay> {
+        let object = self.object(source).ok_or(CannotPay)?;
+        let controller = object.controller;
+        let ability = object.mana_abilities().nth(ability).ok_or(CannotPay)?;
+        if (ability.cost.tap && (object.tapped || object.has_summoning_sickness()))
+            || !ability.can_activate(self, controller)
+            || !ability
+                .produce
+                .outputs(self, controller)
+                .iter()
+                .any(|p| p == produce)
+        {
+            return Err(CannotPay);
+        }
+        let (ability, snow) = (ability.clone(), object.is_snow());
+        if let Some(cost) = &ability.cost.mana_cost {
+            self.player_mut(controller)
+                .mana_pool
+                .pay(cost.as_ref(), 0, 0, None)?;
+        }
+        if ability.cost.tap {
+            self.tap(source);
+        }
+        if ability.cost.sacrifice {
+            self.sacrifice(source);
+        }
+        let pool = &mut self.player_mut(controller).mana_pool;
+        for unit in produce {
+            pool.add(Mana {
+                unit: unit.clone(),
+                restriction: ability.restriction,
+                snow,
+            });
+        }
+        Ok(())
+    }
+
+    // Activates the ability (index among the activated abilities of the permanent `source`),
+    // paying its costs and putting it on the stack. Targets and cards to discard
+    // are chosen by the `agent`. Nothing is paid if the ability cannot be activated.
+    pub fn activate_ability(
+        &mut self,
+        player: PlayerId,
+        source: ObjectId,
+        ability: usize,
+        payment: &PaymentPlan,
+        agent: &mut dyn Agent,
+    ) -> Result<(), CannotPay> {
+        let object = self.object(source).ok_or(CannotPay)?;
+        let ability = object.activated_abilities().nth(ability).ok_or(CannotPay)?;
+        let cost = &ability.cost;
+        let loyalty = object.count_counters(&CounterKind::Loyalty);
+        let can_pay_loyalty = cost.loyalty.is_none_or(|l| {
+            self.has_sorcery_timing(player)
+                && !self.turn.loyalty_activated.contains(&source)
+                && i64::from(loyalty) + i64::from(l) >= 0
+        });
+        if object.controller != player
+            || (cost.tap && (object.tapped || object.has_summoning_sickness()))
+            || (ability.sorcery_speed && !self.has_sorcery_timing(player))
+            || self.player(player).zones.hand.len() < usize::from(cost.discard)
+            || !can_pay_loyalty
+        {
+            return Err(CannotPay);
+        }
+        let (object, ability) = (object.clone(), ability.clone());
+        let targets = self
+            .choose_targets(player, ability.effect.targets(), agent)
+            .ok_or(CannotPay)?;
+
+        self.activate_payment(player, payment)?;
+        if let Some(mana_cost) = &ability.cost.mana_cost {
+            let state = self.player_mut(player);
+            let life_budget = payment
+                .life
+                .min(u32::try_from(state.life_total).unwrap_or(0));
+            let life = state
+                .mana_pool
+                .pay(mana_cost.as_ref(), 0, life_budget, None)? as i32;
+            if life > 0 {
+                self.lose_life(player, life);
+            }
+        }
+        if ability.cost.tap {
+            self.tap(source);
+        }
+        if let Some(loyalty) = ability.cost.loyalty {
+            self.turn.loyalty_activated.push(source);
+            let target = Target::Permanent(source);
+            let amount = loyalty.unsigned_abs();
+            if loyalty >= 0 {
+                self.add_counters(target, CounterKind::Loyalty, amount);
+            } else {
+                self.remove_counters(target, CounterKind::Loyalty, amount);
+            }
+        }
+        let amount = usize::from(ability.cost.discard);
+        for card in agent.choose_cards_to_discard(self, player, amount) {
+            self.discard(player, card);
+        }
+        if ability.cost.sacrifice {
+            self.sacrifice(source);
+        }
+        self.stack.push(StackObject {
+            controller: player,
+            kind: StackObjectKind::Ability(ability.effect),
+            targets,
+            source: Some(object),
+        });
+        self.put_triggers_on_stack(agent);
+        self.priority = Priority::new(player);
+        Ok(())
+    }
+
+    // Does nothing if the card is not in the player's hand.
+    pub fn discard(&mut self, player: PlayerId, card: ObjectId) {
+        if let Some(card) = self.player_mut(player).take_from_hand(card) {
+            let id = card.id;
+            self.player_mut(player)
+                .zones
+                .graveyard
+                .push(card.change_zone());
+            self.log_event(Event::Discard(id));
+        }
+    }
+
+    // Unlike `destroy`, this cannot be prevented (e.g. by a shield counter).
+    pub fn sacrifice(&mut self, id: ObjectId) {
+        self.put_into_graveyard(id);
+    }
+
+    // Damage dealt to a player causes them to lose that much life,
+    // while damage dealt to a creature is marked on it (see `check_state_based_actions`).
+    // TODO: damage prevention.
+    pub fn deal_damage(
+        &mut self,
+        source: &InPlayObject,
+        target: Target,
+        amount: u32,
+        is_combat: bool,
+    ) {
+        if amount == 0 {
+            return;
+        }
+        // A shield counter is removed instead of the permanent being dealt damage.
+        if let Target::Permanent(_) = target
+            && self.remove_counters(target, CounterKind::Shield, 1) > 0
         {
-            let Ability::Other(ability) = cease.abilities.first().expect("Cease has an ability")
-            else {
-                panic!("Cease is an instant");
-            };
-            ability(self);
-            // Used spell goes to the graveyard
-            // TODO: should look up player based on who cast the spell
-            let player = self.players.first_mut().expect("There is a player");
-            player.zones.graveyard.push(card);
             return;
         }
+        match target {
+            Target::Player(player) => self.player_mut(player).life_total -= amount as i32,
+            Target::Permanent(id) => match self.object_mut(id) {
+                Some(object) => {
+                    object.damage += amount;
+                    // Damage to a planeswalker or battle also removes counters
+                    // (the damage marked on it only matters for creatures).
+                    let (is_planeswalker, is_battle) =
+                        (object.is_planeswalker(), object.is_battle());
+                    if is_planeswalker {
+                        self.remove_counters(target, CounterKind::Loyalty, amount);
+                    }
+                    if is_battle {
+                        self.remove_counters(target, CounterKind::Defense, amount);
+                    }
+                }
+                None => return,
+            },
+            Target::CardInGraveyard(_) => return,
+        }
+        self.log_event(Event::Damage {
+            source: source.id(),
+            target,
+            amount,
+            is_combat,
+        });
+        if let Target::Player(player) = target {
+            self.log_event(Event::LostLife(player, amount as i32));
+        }
+        if source.has_ability(|a| matches!(a, Ability::Lifelink)) {
+            self.gain_life(source.controller, amount as i32);
+        }
+    }
 
-        // TODO: there are _so many_ other kinds of etbs
-        let discover_amount = match &card.card {
-            Card::Single(face) => face.abilities.iter().find_map(|a| {
-                if let Ability::Enters(EntersAbility::Discover { amount }) = a {
-                    Some(*amount)
-                } else {
-                    None
+    pub fn gain_life(&mut self, player: PlayerId, amount: i32) {
+        self.player_mut(player).life_total += amount;
+        self.log_event(Event::GainedLife(player, amount));
+    }
+
+    pub fn lose_life(&mut self, player: PlayerId, amount: i32) {
+        self.player_mut(player).life_total -= amount;
+        self.log_event(Event::LostLife(player, amount));
+    }
+
+    // TODO: indestructible and regeneration.
+    pub fn destroy(&mut self, id: ObjectId) {
+        if self.remove_counters(Target::Permanent(id), CounterKind::Shield, 1) > 0 {
+            return;
+        }
+        self.put_into_graveyard(id);
+    }
+
+    // Moves the permanent into its owner's graveyard
+    // (a token then ceases to exist when state-based actions are checked).
+    pub fn put_into_graveyard(&mut self, id: ObjectId) {
+        let Some(index) = self.objects.iter().position(|o| o.id() == id) else {
+            return;
+        };
+        let object = self.objects.remove(index);
+        self.combat.remove(id);
+        self.apply_continuous_effects();
+        let owner = object.card.owner;
+        let card = object.card.clone().change_zone();
+        self.player_mut(owner).zones.graveyard.push(card);
+        if object.is_creature() {
+            self.log_event(Event::Died(Box::new(object)));
+        } else {
+            self.log_event(Event::PutIntoGraveyard(Box::new(object)));
+        }
+    }
+
+    pub fn count_permanents(&self, controller: PlayerId, filter: &PermanentFilter) -> usize {
+        self.objects
+            .iter()
+            .filter(|o| o.controller == controller && filter.matches(o))
+            .count()
+    }
+
+    // Activates the mana abilities of the payment plan.
+    fn activate_payment(
+        &mut self,
+        player: PlayerId,
+        payment: &PaymentPlan,
+    ) -> Result<(), CannotPay> {
+        for activation in &payment.activations {
+            if self.object(activation.source).map(|o| o.controller) != Some(player) {
+                return Err(CannotPay);
+            }
+            self.activate_mana_ability(activation.source, activation.ability, &activation.produce)?;
+        }
+        Ok(())
+    }
+
+    // Carries out the payment plan, then pays the cost of the face(s) being cast
+    // (with the chosen value of X) from the pool.
+    // If the cost cannot be paid, the mana produced stays in the pool.
+    fn pay_spell_cost(
+        &mut self,
+        player: PlayerId,
+        card: ObjectId,
+        face: CastFace,
+        x: u8,
+        payment: &PaymentPlan,
+    ) -> Result<(), CannotPay> {
+        self.activate_payment(player, payment)?;
+        let state = self.player_mut(player);
+        let (card, _) = state.zones.castable(card).ok_or(CannotPay)?;
+        let card = &card.card;
+        let life_budget = payment
+            .life
+            .min(u32::try_from(state.life_total).unwrap_or(0));
+        let cost = spell_cost(card, face);
+        let life = state
+            .mana_pool
+            .pay(cost.as_ref(), x, life_budget, Some((card, face)))? as i32;
+        if life > 0 {
+            self.lose_life(player, life);
+        }
+        Ok(())
+    }
+
+    // Unlocking a door is a special action, taken at sorcery speed by paying the door's cost.
+    // If the cost cannot be paid, the mana produced stays in the pool.
+    pub fn unlock_door(
+        &mut self,
+        player: PlayerId,
+        room: ObjectId,
+        door: usize,
+        payment: &PaymentPlan,
+    ) -> Result<(), CannotPay> {
+        let object = self.object(room).ok_or(CannotPay)?;
+        if object.controller != player
+            || !object.locked_doors().contains(&door)
+            || !self.has_sorcery_timing(player)
+        {
+            return Err(CannotPay);
+        }
+        self.activate_payment(player, payment)?;
+        let object = self.object(room).ok_or(CannotPay)?;
+        let Card::Split(left, right) = &object.card.card else {
+            return Err(CannotPay);
+        };
+        let cost = [left, right][door].mana_cost.clone();
+        if let Some(cost) = cost {
+            let state = self.player_mut(player);
+            let life_budget = payment
+                .life
+                .min(u32::try_from(state.life_total).unwrap_or(0));
+            let life = state.mana_pool.pay(cost.as_ref(), 0, life_budget, None)? as i32;
+            if life > 0 {
+                self.lose_life(player, life);
+            }
+        }
+        let object = self.object_mut(room).ok_or(CannotPay)?;
+        object.unlocked_doors.push(door);
+        self.apply_continuous_effects();
+        self.log_event(Event::UnlockedDoor { room, door });
+        Ok(())
+    }
+
+    // Turns a transforming permanent over; it stays the same object.
+    // Other permanents (e.g. a modal double-faced card) cannot transform.
+    pub fn transform(&mut self, id: ObjectId) {
+        let Some(object) = self.object_mut(id) else {
+            return;
+        };
+        if !matches!(object.card.card, Card::Transforming { .. }) {
+            return;
+        }
+        object.back_face_up = !object.back_face_up;
+        self.apply_continuous_effects();
+        self.log_event(Event::Transformed(id));
+    }
+
+    // Players take actions chosen by the `agent` until all of them pass
+    // in succession with an empty stack (i.e. the step is over).
+    // Actions which are not allowed are treated as passing.
+    pub fn play_priority(&mut self, agent: &mut dyn Agent) {
+        loop {
+            // State-based actions are performed before a player would receive priority.
+            self.check_state_based_actions(agent);
+            self.put_triggers_on_stack(agent);
+            let player = self.priority.holder;
+            match agent.choose_action(self, player) {
+                Action::PlayLand(card)
+                    if self.can_play_land(player)
+                        && self
+                            .hand_card(player, card)
+                            .is_some_and(|c| !c.land_faces().is_empty()) =>
+                {
+                    let card = self.player_mut(player).take_from_hand(card);
+                    self.play_land(card.expect("Card is in hand"), agent);
                 }
-            }),
-            _ => None,
+                // A card on an adventure can only be cast as its primary face.
+                Action::CastSpell {
+                    card,
+                    face,
+                    x,
+                    payment,
+                } if self.player(player).zones.castable(card).is_some_and(
+                    |(c, on_adventure)| {
+                        (!on_adventure || face == CastFace::Face(0))
+                            && self.can_cast(player, &c.card, face)
+                    },
+                ) =>
+                {
+                    // Targets are chosen before the spell is paid for,
+                    // so nothing is paid if there is no legal target.
+                    let (c, _) = self
+                        .player(player)
+                        .zones
+                        .castable(card)
+                        .expect("Checked above");
+                    let targets = self.choose_spell_targets(player, &c.card, face, agent);
+                    let paid = targets.ok_or(CannotPay).and_then(|targets| {
+                        self.pay_spell_cost(player, card, face, x, &payment)
+                            .map(|()| targets)
+                    });
+                    let Ok(targets) = paid else {
+                        if self.pass_priority(agent) == PriorityOutcome::StackEmpty {
+                            return;
+                        }
+                        continue;
+                    };
+                    let zones = &mut self.player_mut(player).zones;
+                    let card = zones.take_castable(card).expect("Card is castable");
+                    self.put_spell_on_stack(player, card, face, x, targets, agent);
+                }
+                Action::UnlockDoor {
+                    room,
+                    door,
+                    payment,
+                } if self.unlock_door(player, room, door, &payment).is_ok() => {}
+                Action::ActivateAbility {
+                    source,
+                    ability,
+                    payment,
+                } if self
+                    .activate_ability(player, source, ability, &payment, agent)
+                    .is_ok() => {}
+                Action::ActivateManaAbility {
+                    source,
+                    ability,
+                    produce,
+                } if self.object(source).map(|o| o.controller) == Some(player)
+                    && self
+                        .activate_mana_ability(source, ability, &produce)
+                        .is_ok() => {}
+                _ => {
+                    if self.pass_priority(agent) == PriorityOutcome::StackEmpty {
+                        return;
+                    }
+                }
+            }
+        }
+    }
+
+    // The player holding priority passes it to the next player.
+    // Once all players pass in succession the top of the stack resolves,
+    // after which the active player receives priority.
+    pub fn pass_priority(&mut self, agent: &mut dyn Agent) -> PriorityOutcome {
+        self.priority.passes += 1;
+        if self.priority.passes < self.players.len() {
+            self.priority.holder = self.next_player(self.priority.holder);
+            return PriorityOutcome::Passed;
+        }
+        let Some(object) = self.stack.pop() else {
+            self.priority = Priority::new(self.turn.active_player);
+            return PriorityOutcome::StackEmpty;
         };
+        self.priority = Priority::new(self.turn.active_player);
+        self.resolve(object, agent);
+        self.put_triggers_on_stack(agent);
+        PriorityOutcome::Resolved
+    }
 
-        let tapped = card.card.enters_tapped();
-        let object = InPlayObject {
-            controller: card.owner, // TODO: depends on caster, not owner
-            is_token: false,
-            counters: Vec::new(),
-            card,
-            tapped,
+    // All players pass priority until the stack is empty.
+    pub fn resolve_stack(&mut self, agent: &mut dyn Agent) {
+        self.check_state_based_actions(agent);
+        self.put_triggers_on_stack(agent);
+        while !self.stack.is_empty() {
+            self.pass_priority(agent);
+            self.check_state_based_actions(agent);
+            self.put_triggers_on_stack(agent);
+        }
+    }
+
+    pub fn player(&self, player: PlayerId) -> &PlayerState {
+        self.players
+            .iter()
+            .find(|p| p.id == player)
+            .expect("Player is in the game")
+    }
+
+    pub fn player_mut(&mut self, player: PlayerId) -> &mut PlayerState {
+        self.players
+            .iter_mut()
+            .find(|p| p.id == player)
+            .expect("Player is in the game")
+    }
+
+    fn hand_card(&self, player: PlayerId, card: ObjectId) -> Option<&Card> {
+        let hand = &self.player(player).zones.hand;
+        hand.iter().find(|c| c.id == card).map(|c| &c.card)
+    }
+
+    fn collect_triggers(&mut self, event: &Event) {
+        let mut triggered = Vec::new();
+        let mut push_triggers = |object: &InPlayObject, condition: TriggerCondition| {
+            for ability in object.abilities() {
+                if let Ability::Triggered(ability) = ability
+                    && ability.condition == condition
+                {
+                    triggered.push(StackObject {
+                        controller: object.controller,
+                        kind: StackObjectKind::Ability(ability.effect.clone()),
+                        targets: Vec::new(),
+                        source: Some(object.clone()),
+                    });
+                }
+            }
         };
-        self.log.push(Event::EnteredPlay(object.clone()));
+        let mut for_each_controlled_by = |player: PlayerId, condition: TriggerCondition| {
+            for object in self.objects.iter().filter(|o| o.controller == player) {
+                push_triggers(object, condition);
+            }
+        };
+
+        match event {
+            Event::EnteredPlay(id) => {
+                if let Some(object) = self.object(*id) {
+                    push_triggers(object, TriggerCondition::Enters)
+                }
+            }
+            // The object has already left the battlefield, so we look back
+            // at the abilities it had.
+            Event::Died(object) => push_triggers(object, TriggerCondition::Dies),
+            Event::Attacked(id) => {
+                if let Some(object) = self.object(*id) {
+                    push_triggers(object, TriggerCondition::Attacks)
+                }
+            }
+            Event::BeginStep(Step::Upkeep) => {
+                for_each_controlled_by(self.turn.active_player, TriggerCondition::Upkeep)
+            }
+            Event::BeginStep(Step::End) => {
+                for_each_controlled_by(self.turn.active_player, TriggerCondition::EndStep)
+            }
+            Event::Cast(card) => {
+                let caster = self.stack.objects.iter().find_map(|o| match &o.kind {
+                    StackObjectKind::Spell { card: spell, .. } if spell.id == *card => {
+                        Some(o.controller)
+                    }
+                    _ => None,
+                });
+                if let Some(caster) = caster {
+                    for_each_controlled_by(caster, TriggerCondition::Cast)
+                }
+            }
+            // Only the door being unlocked has its "when you unlock this door" abilities.
+            Event::UnlockedDoor { room, door } => {
+                if let Some(object) = self.object(*room)
+                    && let Card::Split(left, right) = &object.card.card
+                {
+                    for ability in &[left, right][*door].abilities {
+                        if let Ability::Triggered(ability) = ability
+                            && ability.condition == TriggerCondition::UnlockDoor
+                        {
+                            triggered.push(StackObject {
+                                controller: object.controller,
+                                kind: StackObjectKind::Ability(ability.effect.clone()),
+                                targets: Vec::new(),
+                                source: Some(object.clone()),
+                            });
+                        }
+                    }
+                }
+            }
+            Event::Draw(card) => {
+                let drawn_by = self
+                    .players
+                    .iter()
+                    .find(|p| p.zones.hand.iter().any(|c| c.id == *card));
+                if let Some(player) = drawn_by {
+                    for_each_controlled_by(player.id, TriggerCondition::Draw)
+                }
+            }
+            _ => (),
+        }
+
+        self.pending_triggers.extend(triggered);
+    }
+
+    fn put_triggers_on_stack(&mut self, agent: &mut dyn Agent) {
+        if self.pending_triggers.is_empty() {
+            return;
+        }
+        // The active player's triggers go on the stack first, so they resolve last.
+        let active_player = self.turn.active_player;
+        let (active, others): (Vec<StackObject>, Vec<StackObject>) = self
+            .pending_triggers
+            .drain(..)
+            .partition(|o| o.controller == active_player);
+        for mut object in active.into_iter().chain(others) {
+            let StackObjectKind::Ability(effect) = &object.kind else {
+                continue;
+            };
+            // A triggered ability without legal targets is removed from the stack.
+            let Some(targets) = self.choose_targets(object.controller, effect.targets(), agent)
+            else {
+                continue;
+            };
+            object.targets = targets;
+            self.stack.push(object);
+        }
+        self.priority = Priority::new(active_player);
+    }
+
+    fn resolve(&mut self, object: StackObject, agent: &mut dyn Agent) {
+        let controller = object.controller;
+        match object.kind {
+            StackObjectKind::Spell { card, face, x } => {
+                self.resolve_spell(controller, *card, face, x, object.targets, agent)
+            }
+            StackObjectKind::Ability(effect) => {
+                let targets = self.check_targets(controller, effect.targets(), object.targets);
+                // An ability with targets does nothing if all of them became illegal.
+                if !targets.is_empty() && targets.iter().all(Option::is_none) {
+                    return;
+                }
+                self.resolve_effect(controller, object.source, 0, effect, targets, agent)
+            }
+        }
+    }
+
+    fn resolve_spell(
+        &mut self,
+        controller: PlayerId,
+        card: OwnedCard,
+        face: CastFace,
+        x: u8,
+        targets: Vec<Target>,
+        agent: &mut dyn Agent,
+    ) {
+        let faces = card.card.cast_faces(face);
+        if faces.iter().any(|f| f.is_instant_or_sorcery()) {
+            let effects: Vec<Effect> = (spell_effects(&card.card, face).into_iter())
+                .cloned()
+                .collect();
+            let specs: Vec<TargetSpec> = (effects.iter())
+                .flat_map(|e| e.targets().iter().copied())
+                .collect();
+            let mut targets = self.check_targets(controller, &specs, targets);
+            // A spell with targets does nothing if all of them became illegal.
+            let resolved = targets.is_empty() || targets.iter().any(Option::is_some);
+            if resolved {
+                // The halves of a fused split spell resolve in order, left then right.
+                for effect in effects {
+                    let rest = targets.split_off(effect.targets().len());
+                    self.resolve_effect(controller, None, x, effect, targets, agent);
+                    targets = rest;
+                }
+            }
+            let owner = card.owner;
+            let second_face = face == CastFace::Face(1);
+            match card.card {
+                // The card goes on an adventure, so it can later be cast from exile.
+                Card::Adventure { .. } if resolved && second_face => {
+                    self.player_mut(owner).exile(card, true);
+                }
+                Card::Omen { .. } if resolved && second_face => {
+                    let id = card.id;
+                    self.player_mut(owner).shuffle_into_library(card);
+                    self.log_event(Event::ShuffledIntoLibrary(id));
+                }
+                // Used spell goes to its owner's graveyard
+                _ => self
+                    .player_mut(owner)
+                    .zones
+                    .graveyard
+                    .push(card.change_zone()),
+            }
+            return;
+        }
+
+        // Only instants and sorceries can be fused.
+        let face = match face {
+            CastFace::Face(face) => face,
+            CastFace::Fused => 0,
+        };
+        let mut object = InPlayObject {
+            controller,
+            default_controller: controller,
+            ..InPlayObject::with_face(card, face)
+        };
+        if object.is_siege() {
+            let opponents: Vec<PlayerId> = (self.players.iter())
+                .map(|p| p.id)
+                .filter(|p| *p != controller)
+                .collect();
+            if !opponents.is_empty() {
+                object.protector = Some(agent.choose_protector(self, controller, &opponents));
+            }
+        }
+        let id = object.id();
         self.objects.push(object);
+        self.apply_continuous_effects();
+        self.log_event(Event::EnteredPlay(id));
+    }
 
-        if let Some(amount) = discover_amount {
-            // TODO: should look up player based on who cast the spell
-            let player = self.players.first_mut().expect("There is a player");
-            if let Some(index) = player
-                .zones
-                .library
-                .iter()
-                .position(|c| !c.card.is_land() && c.card.mana_value() <= amount)
-            {
-                let card = player
+    fn resolve_effect(
+        &mut self,
+        controller: PlayerId,
+        source: Option<InPlayObject>,
+        x: u8,
+        effect: Effect,
+        targets: Vec<Option<Target>>,
+        agent: &mut dyn Agent,
+    ) {
+        match effect {
+            Effect::Scry { amount } => {
+                let cards = self.take_from_library(controller, amount);
+                let choices = agent.choose_scry(self, controller, &cards);
+                let mut top = Vec::new();
+                for (index, card) in cards.into_iter().enumerate() {
+                    match choices.get(index).copied().unwrap_or(ScryChoice::Top) {
+                        ScryChoice::Top => {
+                            self.log_event(Event::ScryTop(card.id));
+                            top.push(card);
+                        }
+                        ScryChoice::Bottom => {
+                            self.log_event(Event::ScryBottom(card.id));
+                            self.player_mut(controller).zones.library.push_back(card);
+                        }
+                    }
+                }
+                let library = &mut self.player_mut(controller).zones.library;
+                for card in top.into_iter().rev() {
+                    library.push_front(card);
+                }
+            }
+            Effect::Surveil { amount } => {
+                let cards = self.take_from_library(controller, amount);
+                let choices = agent.choose_surveil(self, controller, &cards);
+                let mut top = Vec::new();
+                for (index, card) in cards.into_iter().enumerate() {
+                    match choices.get(index).copied().unwrap_or(SurveilChoice::Top) {
+                        SurveilChoice::Top => {
+                            self.log_event(Event::SurveilTop(card.id));
+                            top.push(card);
+                        }
+                        SurveilChoice::Graveyard => {
+                            self.log_event(Event::SurveilYard(card.id));
+                            let card = card.change_zone();
+                            self.player_mut(controller).zones.graveyard.push(card);
+                        }
+                    }
+                }
+                let library = &mut self.player_mut(controller).zones.library;
+                for card in top.into_iter().rev() {
+                    library.push_front(card);
+                }
+            }
+            Effect::Discover { amount } => {
+                // TODO: players can have choices within the ETB. For example discover allows to hand.
+                let player = self.player_mut(controller);
+                if let Some(index) = player
                     .zones
                     .library
-                    .remove(index)
-                    .expect("Card is present in find");
-                self.log.push(Event::DiscoverInto(Some(card.clone())));
-                // TODO: can allow player to decide to go to hand instead
-                self.cast_spell(card);
-            } else {
-                self.log.push(Event::DiscoverInto(None));
+                    .iter()
+                    .position(|c| !c.card.is_land() && c.card.mana_value() <= amount)
+                {
+                    let card = player
+                        .zones
+                        .library
+                        .remove(index)
+                        .expect("Card is present in find");
+                    self.log_event(Event::DiscoverInto(Some(card.id)));
+                    // TODO: can allow player to decide to go to hand instead
+                    // TODO: choose the face to cast (e.g. the door of a Room).
+                    let face = CastFace::Face(0);
+                    if let Err(NoLegalTargets(card)) = self.cast_spell(card, face, agent) {
+                        self.player_mut(controller).exile(*card, false);
+                    }
+                } else {
+                    self.log_event(Event::DiscoverInto(None));
+                }
+            }
+            Effect::GainLife { amount } => self.gain_life(controller, amount.into()),
+            Effect::Draw { amount } => {
+                for _ in 0..amount {
+                    self.draw_card(controller).ok();
+                }
             }
+            // Reveal the top card of the library. A land is put into the hand,
+            // otherwise the creature gets a +1/+1 counter and the card may be put into the graveyard.
+            Effect::Explore => {
+                let Some(Some(creature)) = targets.first().copied() else {
+                    return;
+                };
+                let card = self.take_from_library(controller, 1).pop();
+                if let Some(card) = &card
+                    && card.card.is_land()
+                {
+                    self.put_into_hand(card.clone());
+                } else {
+                    self.add_counters(creature, CounterKind::PlusOnePlusOne, 1);
+                    if let Some(card) = card {
+                        if agent.choose_explore_to_graveyard(self, controller, &card) {
+                            let card = card.change_zone();
+                            self.player_mut(controller).zones.graveyard.push(card);
+                        } else {
+                            self.player_mut(controller).zones.library.push_front(card);
+                        }
+                    }
+                }
+                if let Target::Permanent(id) = creature {
+                    self.log_event(Event::Explored(id));
+                }
+            }
+            Effect::Other { resolve, .. } => resolve(
+                self,
+                &Resolution {
+                    controller,
+                    source,
+                    x,
+                    targets,
+                },
+            ),
         }
     }
+
+    // Removes up to `amount` cards from the top of the library (e.g. to reveal them).
+    pub fn take_from_library(&mut self, player: PlayerId, amount: u8) -> Vec<OwnedCard> {
+        let library = &mut self.player_mut(player).zones.library;
+        let amount = library.len().min(amount.into());
+        library.drain(..amount).collect()
+    }
 }
 
-// TODO: Magic has a notion of timestamps not yet present here.
-// TODO: Need to model modifications (aura's, equipment, pump spells, anthems, etc).
+// The effects of an instant or sorcery spell cast as the `face`, in order of resolution;
+// permanent spells have none.
+fn spell_effects(card: &Card, face: CastFace) -> Vec<&Effect> {
+    (card.cast_faces(face).into_iter())
+        .filter(|f| f.is_instant_or_sorcery())
+        .filter_map(|f| {
+            f.abilities.iter().find_map(|a| match a {
+                Ability::Spell(effect) => Some(effect),
+                _ => None,
+            })
+        })
+        .collect()
+}
+
+// Indices of the faces the card can be cast as.
+fn spell_faces(card: &Card) -> Vec<usize> {
+    let faces = card.faces();
+    // Only the front face of a transforming card can be cast.
+    let castable = match card {
+        Card::Transforming { .. } => 1,
+        _ => faces.len(),
+    };
+    // A land face is played rather than cast.
+    (0..castable).filter(|i| !faces[*i].is_land()).collect()
+}
+
+// The total cost of the face(s) being cast (e.g. both halves of a fused split card).
+fn spell_cost(card: &Card, face: CastFace) -> ManaCost {
+    let pips = (card.cast_faces(face).into_iter())
+        .filter_map(|f| f.mana_cost.as_ref())
+        .flat_map(|c| c.pips.iter().cloned())
+        .collect();
+    ManaCost { pips }
+}
+
+// The faces of a card on the battlefield.
+fn faces<'a>(card: &'a Card, unlocked_doors: &[usize], back_face_up: bool) -> Vec<&'a CardFace> {
+    match card {
+        Card::Single(face) => vec![face],
+        Card::Split(left, right) => [left, right]
+            .into_iter()
+            .enumerate()
+            .filter(|(door, _)| unlocked_doors.contains(door))
+            .map(|(_, face)| face)
+            .collect(),
+        Card::Adventure { primary, .. } | Card::Omen { primary, .. } => vec![primary],
+        Card::ModalDoubleFaced { front, back } | Card::Transforming { front, back } => {
+            vec![if back_face_up { back } else { front }]
+        }
+    }
+}
+
+// A planeswalker enters with its printed loyalty, and a battle with its printed defense.
+fn entering_counters(faces: &[&CardFace]) -> Vec<Counters> {
+    let mut counters = Vec::new();
+    for face in faces {
+        let (kind, amount) = match face.type_line.without_kindred() {
+            Type::Planeswalker(planeswalker) => (CounterKind::Loyalty, planeswalker.loyalty),
+            Type::Battle(battle) => (CounterKind::Defense, battle.defense),
+            _ => continue,
+        };
+        if amount > 0 {
+            counters.push(Counters { kind, amount });
+        }
+    }
+    counters
+}
+
+fn printed_characteristics(
+    card: &Card,
+    unlocked_doors: &[usize],
+    back_face_up: bool,
+) -> Characteristics {
+    let mut characteristics = Characteristics::printed(&faces(card, unlocked_doors, back_face_up));
+    // Only the name, mana cost and rules text of a locked door are ignored.
+    if let Card::Split(left, right) = card {
+        characteristics.type_lines = vec![left.type_line.clone(), right.type_line.clone()];
+    }
+    characteristics
+}
+
+// TODO: auras and equipment (attaching permanents to each other).
 #[derive(Debug, Clone)]
 pub struct InPlayObject {
     pub controller: PlayerId,
-    pub is_token: bool,
+    // The player under whose control the permanent entered the battlefield.
+    // Effects in the control layer can change `controller`.
+    pub default_controller: PlayerId,
+    pub timestamp: Timestamp,
+    // Computed from the card by `Battlefield::apply_continuous_effects`.
+    pub characteristics: Characteristics,
+    // Indices of the unlocked doors of a Room (a split card), which only has the abilities
+    // of its unlocked doors. A Room put onto the battlefield without being cast has none.
+    pub unlocked_doors: Vec<usize>,
+    // Whether a double-faced card is on the battlefield with its back face up.
+    pub back_face_up: bool,
+    // The opponent who protects a Siege (a battle), and can be attacked by everyone else.
+    pub protector: Option<PlayerId>,
     pub counters: Vec<Counters>,
     pub card: OwnedCard,
     pub tapped: bool,
+    // Damage marked on the permanent this turn
+    pub damage: u32,
+    // Whether the object came under its controller's control since the start of their most
+    // recent turn. Creatures cannot attack or pay {T} costs while this is set (see `has_summoning_sickness`).
+    pub summoning_sick: bool,
+}
+
+impl InPlayObject {
+    // A permanent entering the battlefield under its owner's control.
+    // The card becomes a new object as it changes zones.
+    pub fn new(card: OwnedCard) -> Self {
+        let card = card.change_zone();
+        let characteristics = printed_characteristics(&card.card, &[], false);
+        let counters = entering_counters(&faces(&card.card, &[], false));
+        Self {
+            controller: card.owner,
+            default_controller: card.owner,
+            timestamp: Timestamp::next(),
+            characteristics,
+            unlocked_doors: Vec::new(),
+            back_face_up: false,
+            protector: None,
+            counters,
+            tapped: card.card.enters_tapped(),
+            card,
+            damage: 0,
+            summoning_sick: true,
+        }
+    }
+
+    // A permanent entering with the `face` which was cast or played up
+    // (e.g. the land back of a modal double-faced card, or the unlocked door of a Room).
+    pub fn with_face(card: OwnedCard, face: usize) -> Self {
+        let mut object = Self::new(card);
+        match object.card.card {
+            Card::Split(..) => object.unlocked_doors = vec![face],
+            Card::ModalDoubleFaced { .. } | Card::Transforming { .. } => {
+                object.back_face_up = face == 1
+            }
+            _ => return object,
+        }
+        object.characteristics = object.printed_characteristics();
+        object.counters = entering_counters(&object.faces());
+        object.tapped = object.faces().iter().any(|f| f.enters_tapped());
+        object
+    }
+
+    pub fn id(&self) -> ObjectId {
+        self.card.id
+    }
+
+    // The printed faces of the card (see `characteristics` for their current values).
+    // Locked doors of a Room, and the face down side of a double-faced card, are left out.
+    pub fn faces(&self) -> Vec<&CardFace> {
+        faces(&self.card.card, &self.unlocked_doors, self.back_face_up)
+    }
+
+    pub fn printed_characteristics(&self) -> Characteristics {
+        printed_characteristics(&self.card.card, &self.unlocked_doors, self.back_face_up)
+    }
+
+    pub fn locked_doors(&self) -> Vec<usize> {
+        match self.card.card {
+            Card::Split(..) => (0..2)
+                .filter(|d| !self.unlocked_doors.contains(d))
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    pub fn abilities(&self) -> impl Iterator<Item = &Ability> {
+        self.characteristics.abilities.iter()
+    }
+
+    pub fn mana_abilities(&self) -> impl Iterator<Item = &ManaAbility> {
+        self.abilities().filter_map(|a| match a {
+            Ability::Mana(ability) => Some(ability),
+            _ => None,
+        })
+    }
+
+    pub fn count_counters(&self, kind: &CounterKind) -> u32 {
+        counters::count(&self.counters, kind)
+    }
+
+    pub fn activated_abilities(&self) -> impl Iterator<Item = &ActivatedAbility> {
+        self.abilities().filter_map(|a| match a {
+            Ability::Activated(ability) => Some(ability),
+            _ => None,
+        })
+    }
+
+    pub fn is_token(&self) -> bool {
+        self.card.is_token
+    }
+
+    pub fn has_ability(&self, predicate: impl Fn(&Ability) -> bool) -> bool {
+        self.abilities().any(predicate)
+    }
+
+    pub fn is_creature(&self) -> bool {
+        let type_lines = &self.characteristics.type_lines;
+        type_lines.iter().any(|t| t.creature().is_some())
+    }
+
+    pub fn is_planeswalker(&self) -> bool {
+        let type_lines = &self.characteristics.type_lines;
+        (type_lines.iter()).any(|t| matches!(t.without_kindred(), Type::Planeswalker(_)))
+    }
+
+    pub fn is_battle(&self) -> bool {
+        let type_lines = &self.characteristics.type_lines;
+        (type_lines.iter()).any(|t| matches!(t.without_kindred(), Type::Battle(_)))
+    }
+
+    pub fn is_siege(&self) -> bool {
+        self.characteristics
+            .type_lines
+            .iter()
+            .any(|t| match t.without_kindred() {
+                Type::Battle(battle) => battle.subtypes.contains(&BattleSubtypes::Siege),
+                _ => false,
+            })
+    }
+
+    pub fn is_legendary(&self) -> bool {
+        self.characteristics.is_legendary
+    }
+
+    pub fn is_snow(&self) -> bool {
+        self.characteristics.is_snow
+    }
+
+    pub fn has_summoning_sickness(&self) -> bool {
+        self.summoning_sick
+            && self.is_creature()
+            && !self.has_ability(|a| matches!(a, Ability::Haste))
+    }
+
+    // Negative power deals no damage.
+    pub fn power(&self) -> u32 {
+        self.characteristics.power.unwrap_or(0).max(0) as u32
+    }
+
+    pub fn toughness(&self) -> u32 {
+        self.characteristics.toughness.unwrap_or(0).max(0) as u32
+    }
+
+    pub fn has_zero_toughness(&self) -> bool {
+        self.characteristics.toughness.is_some_and(|t| t <= 0)
+    }
+
+    // The amount of damage which would destroy the creature,
+    // taking into account damage already marked on it.
+    pub fn lethal_damage(&self) -> u32 {
+        self.toughness().saturating_sub(self.damage)
+    }
 }
 
 /// Events that can happen during a game (e.g. play a land, scry 1 top, etc.).
@@ -137,15 +1446,962 @@ pub struct InPlayObject {
 pub enum Event {
     Mulligan(PlayerId),
     StartTurn(PlayerId),
-    EnteredPlay(InPlayObject),
-    Tap(InPlayObject),
-    Untap(InPlayObject),
-    DiscoverInto(Option<OwnedCard>),
-    Draw(OwnedCard),
+    BeginStep(Step),
+    EnteredPlay(ObjectId),
+    // Permanents which left the battlefield are logged as they last existed there,
+    // so abilities which trigger on them leaving can look back at them.
+    Died(Box<InPlayObject>),
+    // A non-creature permanent was put into its owner's graveyard.
+    PutIntoGraveyard(Box<InPlayObject>),
+    LostGame(PlayerId),
+    Attacked(ObjectId),
+    Blocked {
+        blocker: ObjectId,
+        attacker: ObjectId,
+    },
+    Damage {
+        source: ObjectId,
+        target: Target,
+        amount: u32,
+        is_combat: bool,
+    },
+    Tap(ObjectId),
+    Untap(ObjectId),
+    // The id of the spell on the stack
+    Cast(ObjectId),
+    DiscoverInto(Option<ObjectId>),
+    Draw(ObjectId),
+    // The id of the card in hand, for a card put there other than by drawing it.
+    PutIntoHand(ObjectId),
+    // The id of the card in hand
+    Discard(ObjectId),
+    Explored(ObjectId),
+    // An Omen spell was shuffled into its owner's library as it resolved.
+    ShuffledIntoLibrary(ObjectId),
+    Transformed(ObjectId),
+    UnlockedDoor {
+        room: ObjectId,
+        door: usize,
+    },
+    GainedLife(PlayerId, i32),
     LostLife(PlayerId, i32),
-    ScryTop(OwnedCard),
-    ScryBottom(OwnedCard),
-    SurveilTop(OwnedCard),
-    SurveilYard(OwnedCard),
+    AddedCounters {
+        target: Target,
+        kind: CounterKind,
+        amount: u32,
+    },
+    RemovedCounters {
+        target: Target,
+        kind: CounterKind,
+        amount: u32,
+    },
+    ScryTop(ObjectId),
+    ScryBottom(ObjectId),
+    SurveilTop(ObjectId),
+    SurveilYard(ObjectId),
     EndTurn(PlayerId),
 }
+
+#[test]
+fn test_stack_resolves_last_in_first_out() {
+    use crate::{
+        card::{test_card, types::ArtifactProperties},
+        game_play::agent::Goldfish,
+    };
+
+    let permanent = |name: &str| {
+        test_card(
+            name,
+            Type::Artifact(ArtifactProperties {
+                subtypes: Vec::new(),
+            }),
+            Vec::new(),
+        )
+    };
+    let mut battlefield = Battlefield::new(vec![
+        PlayerState::new(0, vec![permanent("First"), permanent("Second")]),
+        PlayerState::new(1, Vec::new()),
+    ]);
+    let player = battlefield.players.first_mut().unwrap();
+    let first = player.zones.library.pop_front().unwrap();
+    let second = player.zones.library.pop_front().unwrap();
+    let first_name = first.card.primary_name().unwrap().to_string();
+    let second_name = second.card.primary_name().unwrap().to_string();
+
+    battlefield
+        .cast_spell(first, CastFace::Face(0), &mut Goldfish)
+        .unwrap();
+    // Cast the second spell in response to the first
+    battlefield
+        .cast_spell(second, CastFace::Face(0), &mut Goldfish)
+        .unwrap();
+    assert_eq!(battlefield.stack.objects.len(), 2);
+
+    // Both players must pass for the top of the stack to resolve
+    assert_eq!(
+        battlefield.pass_priority(&mut Goldfish),
+        PriorityOutcome::Passed
+    );
+    assert_eq!(
+        battlefield.pass_priority(&mut Goldfish),
+        PriorityOutcome::Resolved
+    );
+    assert_eq!(battlefield.stack.objects.len(), 1);
+    assert_eq!(
+        battlefield.objects[0].card.card.primary_name(),
+        Some(second_name.as_str())
+    );
+
+    battlefield.resolve_stack(&mut Goldfish);
+    assert!(battlefield.stack.is_empty());
+    assert_eq!(
+        battlefield.objects[1].card.card.primary_name(),
+        Some(first_name.as_str())
+    );
+    assert_eq!(
+        battlefield.pass_priority(&mut Goldfish),
+        PriorityOutcome::Passed
+    );
+    assert_eq!(
+        battlefield.pass_priority(&mut Goldfish),
+        PriorityOutcome::StackEmpty
+    );
+}
+
+#[test]
+fn test_enters_trigger_goes_on_stack() {
+    use crate::{
+        card::{
+            abilities::TriggeredAbility,
+            test_card,
+            types::{LandProperties, Type},
+        },
+        game_play::agent::Goldfish,
+    };
+
+    let land = test_card(
+        "Life Land",
+        Type::Land(LandProperties {
+            is_basic: false,
+            subtypes: Vec::new(),
+        }),
+        vec![Ability::Triggered(TriggeredAbility {
+            condition: TriggerCondition::Enters,
+            effect: Effect::GainLife { amount: 1 },
+        })],
+    );
+    let mut battlefield = Battlefield::new(vec![PlayerState::new(0, vec![land])]);
+    let card = battlefield.players[0].zones.library.pop_front().unwrap();
+
+    battlefield.play_land(card, &mut Goldfish);
+    assert_eq!(battlefield.stack.objects.len(), 1);
+    assert_eq!(battlefield.players[0].life_total, 20);
+
+    battlefield.resolve_stack(&mut Goldfish);
+    assert!(battlefield.stack.is_empty());
+    assert_eq!(battlefield.players[0].life_total, 21);
+}
+
+#[test]
+fn test_london_mulligan() {
+    use crate::card::{test_card, types::LandProperties};
+
+    struct MulliganOnce;
+    impl Agent for MulliganOnce {
+        fn keep_hand(&mut self, _: &Battlefield, _: PlayerId, mulligans: usize) -> bool {
+            mulligans > 0
+        }
+    }
+
+    let land = test_card(
+        "Wastes",
+        Type::Land(LandProperties {
+            is_basic: true,
+            subtypes: Vec::new(),
+        }),
+        Vec::new(),
+    );
+    let mut battlefield = Battlefield::new(vec![PlayerState::new(0, vec![land; 40])]);
+    let player = battlefield.players[0].id;
+
+    battlefield.draw_opening_hand(player, &mut MulliganOnce);
+    let zones = &battlefield.players[0].zones;
+    assert_eq!(zones.hand.len(), 6);
+    assert_eq!(zones.library.len(), 34);
+    assert_eq!(
+        battlefield
+            .log
+            .iter()
+            .filter(|e| matches!(e, Event::Mulligan(_)))
+            .count(),
+        1
+    );
+}
+
+#[test]
+fn test_first_draw_skipped() {
+    use crate::{
+        card::{test_card, types::LandProperties},
+        game_play::agent::Goldfish,
+    };
+
+    let land = test_card(
+        "Wastes",
+        Type::Land(LandProperties {
+            is_basic: true,
+            subtypes: Vec::new(),
+        }),
+        Vec::new(),
+    );
+    let mut battlefield = Battlefield::new(vec![
+        PlayerState::new(0, vec![land.clone(); 10]),
+        PlayerState::new(1, vec![land; 10]),
+    ]);
+    let (first, second) = (battlefield.players[0].id, battlefield.players[1].id);
+    let take_turn = |battlefield: &mut Battlefield, player: PlayerId| {
+        battlefield.start_turn(player);
+        while battlefield.advance_step(&mut Goldfish).is_some() {}
+    };
+
+    // The player who goes first does not draw on their first turn, but the other player does.
+    take_turn(&mut battlefield, first);
+    assert!(battlefield.player(first).zones.hand.is_empty());
+    take_turn(&mut battlefield, second);
+    assert_eq!(battlefield.player(second).zones.hand.len(), 1);
+    take_turn(&mut battlefield, first);
+    assert_eq!(battlefield.player(first).zones.hand.len(), 1);
+}
+
+#[test]
+fn test_rooms() {
+    use crate::{
+        card::{
+            abilities::TriggeredAbility,
+            color::Color,
+            test_face,
+            types::{EnchantmentProperties, EnchantmentSubtypes},
+        },
+        mana::pool::Mana,
+    };
+
+    // Casts the room's first door, then unlocks the second.
+    struct RoomPlayer;
+    impl Agent for RoomPlayer {
+        fn choose_action(&mut self, battlefield: &Battlefield, player: PlayerId) -> Action {
+            if let Some(card) = battlefield.player(player).zones.hand.first() {
+                return Action::CastSpell {
+                    card: card.id,
+                    face: CastFace::Face(0),
+                    x: 0,
+                    payment: PaymentPlan::default(),
+                };
+            }
+            match battlefield.objects.first() {
+                Some(room) if !room.locked_doors().is_empty() => Action::UnlockDoor {
+                    room: room.id(),
+                    door: 1,
+                    payment: PaymentPlan::default(),
+                },
+                _ => Action::Pass,
+            }
+        }
+    }
+
+    let door = |name: &str, mana_cost: &str, condition: TriggerCondition| CardFace {
+        mana_cost: Some(mana_cost.parse().unwrap()),
+        color: Color::red(),
+        ..test_face(
+            name,
+            Type::Enchantment(EnchantmentProperties {
+                subtypes: vec![EnchantmentSubtypes::Room],
+            }),
+            vec![Ability::Triggered(TriggeredAbility {
+                condition,
+                effect: Effect::GainLife { amount: 1 },
+            })],
+        )
+    };
+    let room = Card::Split(
+        door("Front Door", "{R}", TriggerCondition::UnlockDoor),
+        door("Back Door", "{1}{R}", TriggerCondition::UnlockDoor),
+    );
+    let mut battlefield = Battlefield::new(vec![PlayerState::new(0, vec![room])]);
+    let card = battlefield.players[0].zones.library.pop_front().unwrap();
+    battlefield.players[0].zones.hand.push(card);
+    battlefield.turn.step = Step::PrecombatMain;
+    let add_mana = |battlefield: &mut Battlefield, amount: usize| {
+        for _ in 0..amount {
+            battlefield.players[0].mana_pool.add(Mana {
+                unit: Unit::Red,
+                restriction: None,
+                snow: false,
+            });
+        }
+    };
+
+    // Casting the front door does not unlock it.
+    add_mana(&mut battlefield, 1);
+    battlefield.play_priority(&mut RoomPlayer);
+    let room = &battlefield.objects[0];
+    assert_eq!(room.unlocked_doors, vec![0]);
+    assert_eq!(room.characteristics.name, "Front Door");
+    assert_eq!(room.abilities().count(), 1);
+    assert_eq!(battlefield.players[0].life_total, 20);
+
+    // Unlocking the back door triggers only its own ability.
+    add_mana(&mut battlefield, 2);
+    battlefield.play_priority(&mut RoomPlayer);
+    let room = &battlefield.objects[0];
+    assert!(room.locked_doors().is_empty());
+    assert_eq!(room.characteristics.name, "Front Door // Back Door");
+    assert_eq!(battlefield.players[0].life_total, 21);
+}
+
+#[test]
+fn test_double_faced_cards() {
+    use crate::{
+        card::{
+            color::Color,
+            test_face,
+            types::{CreatureProperties, LandProperties, Power, Toughness},
+        },
+        game_play::agent::Goldfish,
+    };
+
+    let creature = |name: &str, mana_cost: Option<&str>, power: u32, toughness: u32| CardFace {
+        mana_cost: mana_cost.map(|c| c.parse().unwrap()),
+        color: Color::green(),
+        ..test_face(
+            name,
+            Type::Creature(CreatureProperties {
+                subtypes: Vec::new(),
+                power: Power::Value(power),
+                toughness: Toughness::Value(toughness),
+            }),
+            Vec::new(),
+        )
+    };
+    let mdfc = Card::ModalDoubleFaced {
+        front: creature("Front Creature", Some("{3}{G}"), 3, 3),
+        back: test_face(
+            "Back Land",
+            Type::Land(LandProperties {
+                is_basic: false,
+                subtypes: Vec::new(),
+            }),
+            vec![Ability::EntersTapped],
+        ),
+    };
+    let werewolf = Card::Transforming {
+        front: creature("Human", Some("{1}{G}"), 2, 2),
+        back: creature("Werewolf", None, 4, 4),
+    };
+    assert!(!mdfc.is_land());
+    assert_eq!(mdfc.land_faces(), vec![1]);
+    assert_eq!(mdfc.mana_value(), 4);
+    assert_eq!(werewolf.primary_name(), Some("Human"));
+    assert_eq!(werewolf.mana_value(), 2);
+
+    let mut battlefield = Battlefield::new(vec![PlayerState::new(0, vec![mdfc, werewolf])]);
+    let player = battlefield.players[0].id;
+    battlefield.turn.step = Step::PrecombatMain;
+    for _ in 0..2 {
+        battlefield.draw_card(player).unwrap();
+    }
+
+    // The modal double-faced card is played as a land with its back face up.
+    battlefield.play_priority(&mut Goldfish);
+    let land = &battlefield.objects[0];
+    assert!(land.back_face_up);
+    assert_eq!(land.characteristics.name, "Back Land");
+    assert!(land.tapped);
+
+    // The transformed permanent has the back face's characteristics, and is the same object.
+    let werewolf = battlefield.players[0].zones.hand.pop().unwrap();
+    battlefield.objects.push(InPlayObject::new(werewolf));
+    let id = battlefield.objects[1].id();
+    battlefield.transform(id);
+    let werewolf = battlefield.object(id).unwrap();
+    assert_eq!(werewolf.characteristics.name, "Werewolf");
+    assert_eq!((werewolf.power(), werewolf.toughness()), (4, 4));
+    assert_eq!(werewolf.card.card.mana_value(), 2);
+    battlefield.transform(id);
+    assert_eq!(battlefield.object(id).unwrap().power(), 2);
+}
+
+#[test]
+fn test_adventure() {
+    use crate::{
+        card::{
+            color::Color,
+            test_face,
+            types::{CreatureProperties, Power, Toughness},
+        },
+        mana::pool::Mana,
+    };
+
+    // Casts the card from hand, or from exile, as the face.
+    struct Adventurer(CastFace);
+    impl Agent for Adventurer {
+        fn choose_action(&mut self, battlefield: &Battlefield, player: PlayerId) -> Action {
+            let zones = &battlefield.player(player).zones;
+            let card = zones.hand.first().or(zones.exile.first().map(|c| &c.card));
+            match card {
+                Some(card) => Action::CastSpell {
+                    card: card.id,
+                    face: self.0,
+                    x: 0,
+                    payment: PaymentPlan::default(),
+                },
+                None => Action::Pass,
+            }
+        }
+    }
+
+    let card = Card::Adventure {
+        primary: CardFace {
+            mana_cost: Some("{2}{G}".parse().unwrap()),
+            color: Color::green(),
+            ..test_face(
+                "Giant",
+                Type::Creature(CreatureProperties {
+                    subtypes: Vec::new(),
+                    power: Power::Value(4),
+                    toughness: Toughness::Value(4),
+                }),
+                Vec::new(),
+            )
+        },
+        adventure: CardFace {
+            mana_cost: Some("{G}".parse().unwrap()),
+            color: Color::green(),
+            ..test_face(
+                "Feast",
+                Type::Sorcery,
+                vec![Ability::Spell(Effect::GainLife { amount: 2 })],
+            )
+        },
+    };
+    let mut battlefield = Battlefield::new(vec![PlayerState::new(0, vec![card])]);
+    let card = battlefield.players[0].zones.library.pop_front().unwrap();
+    battlefield.players[0].zones.hand.push(card);
+    battlefield.turn.step = Step::PrecombatMain;
+    let add_mana = |battlefield: &mut Battlefield, amount: usize| {
+        for _ in 0..amount {
+            battlefield.players[0].mana_pool.add(Mana {
+                unit: Unit::Green,
+                restriction: None,
+                snow: false,
+            });
+        }
+    };
+
+    // The adventure resolves, and the card is exiled on an adventure.
+    add_mana(&mut battlefield, 1);
+    battlefield.play_priority(&mut Adventurer(CastFace::Face(1)));
+    let zones = &battlefield.players[0].zones;
+    assert_eq!(battlefield.players[0].life_total, 22);
+    assert!(zones.hand.is_empty() && zones.graveyard.is_empty());
+    assert!(zones.exile[0].on_adventure);
+
+    // The adventure cannot be cast again from exile, but the creature can.
+    add_mana(&mut battlefield, 1);
+    battlefield.play_priority(&mut Adventurer(CastFace::Face(1)));
+    assert_eq!(battlefield.players[0].zones.exile.len(), 1);
+    add_mana(&mut battlefield, 2);
+    battlefield.play_priority(&mut Adventurer(CastFace::Face(0)));
+    assert!(battlefield.players[0].zones.exile.is_empty());
+    assert_eq!(battlefield.objects[0].characteristics.name, "Giant");
+    assert_eq!(battlefield.players[0].life_total, 22);
+}
+
+#[test]
+fn test_omen() {
+    use crate::{
+        card::{
+            color::Color,
+            test_face,
+            types::{CreatureProperties, Power, Toughness},
+        },
+        mana::pool::Mana,
+    };
+
+    struct CastFromHand;
+    impl Agent for CastFromHand {
+        fn choose_action(&mut self, battlefield: &Battlefield, player: PlayerId) -> Action {
+            match battlefield.player(player).zones.hand.first() {
+                Some(card) => Action::CastSpell {
+                    card: card.id,
+                    face: CastFace::Face(1),
+                    x: 0,
+                    payment: PaymentPlan::default(),
+                },
+                None => Action::Pass,
+            }
+        }
+    }
+
+    let card = Card::Omen {
+        primary: CardFace {
+            mana_cost: Some("{3}{R}{R}".parse().unwrap()),
+            color: Color::red(),
+            ..test_face(
+                "Dragon",
+                Type::Creature(CreatureProperties {
+                    subtypes: Vec::new(),
+                    power: Power::Value(5),
+                    toughness: Toughness::Value(5),
+                }),
+                Vec::new(),
+            )
+        },
+        omen: CardFace {
+            mana_cost: Some("{R}".parse().unwrap()),
+            color: Color::red(),
+            ..test_face(
+                "Breath",
+                Type::Instant,
+                vec![Ability::Spell(Effect::GainLife { amount: 3 })],
+            )
+        },
+    };
+    assert!(!card.is_land());
+    let mut battlefield = Battlefield::new(vec![PlayerState::new(0, vec![card])]);
+    let card = battlefield.players[0].zones.library.pop_front().unwrap();
+    battlefield.players[0].zones.hand.push(card);
+    battlefield.turn.step = Step::PrecombatMain;
+    battlefield.players[0].mana_pool.add(Mana {
+        unit: Unit::Red,
+        restriction: None,
+        snow: false,
+    });
+
+    // The omen resolves, and the card is shuffled into the library instead of the graveyard.
+    battlefield.play_priority(&mut CastFromHand);
+    let zones = &battlefield.players[0].zones;
+    assert_eq!(battlefield.players[0].life_total, 23);
+    assert!(zones.hand.is_empty() && zones.graveyard.is_empty());
+    assert_eq!(zones.library.len(), 1);
+    let shuffled = |e: &Event| matches!(e, Event::ShuffledIntoLibrary(_));
+    assert!(battlefield.log.iter().any(shuffled));
+}
+
+#[test]
+fn test_fused_split_card() {
+    use crate::{
+        card::{color::Color, test_face},
+        game_play::agent::Goldfish,
+        mana::pool::Mana,
+    };
+
+    let half = |name: &str, mana_cost: &str, amount: u8| CardFace {
+        mana_cost: Some(mana_cost.parse().unwrap()),
+        color: Color::white(),
+        ..test_face(
+            name,
+            Type::Instant,
+            vec![Ability::Spell(Effect::GainLife { amount }), Ability::Fuse],
+        )
+    };
+    let card = Card::Split(half("Left", "{W}", 1), half("Right", "{2}{W}", 2));
+    assert_eq!(card.mana_value(), 4);
+    assert_eq!(card.cast_mana_value(CastFace::Face(1), 0), 3);
+
+    let mut battlefield = Battlefield::new(vec![PlayerState::new(0, vec![card])]);
+    let card = battlefield.players[0].zones.library.pop_front().unwrap();
+    let player = battlefield.players[0].id;
+    assert!(battlefield.can_cast(player, &card.card, CastFace::Fused));
+    assert!(!battlefield.can_cast(player, &card.card, CastFace::Face(2)));
+
+    // Both halves are paid for, and resolve as one spell.
+    let id = card.id;
+    battlefield.players[0].zones.hand.push(card);
+    for _ in 0..4 {
+        battlefield.players[0].mana_pool.add(Mana {
+            unit: Unit::White,
+            restriction: None,
+            snow: false,
+        });
+    }
+    let payment = PaymentPlan::default();
+    let result = battlefield.pay_spell_cost(player, id, CastFace::Fused, 0, &payment);
+    assert!(result.is_ok());
+    let card = battlefield.player_mut(player).take_from_hand(id).unwrap();
+    let result = battlefield.cast_spell(card, CastFace::Fused, &mut Goldfish);
+    assert!(result.is_ok());
+    let spell = battlefield.stack.top().unwrap();
+    assert_eq!(spell.mana_value(), 4);
+    assert_eq!(spell.characteristics().unwrap().name, "Left // Right");
+    battlefield.resolve_stack(&mut Goldfish);
+    assert_eq!(battlefield.players[0].life_total, 23);
+    assert_eq!(battlefield.players[0].zones.graveyard.len(), 1);
+}
+
+#[test]
+fn test_no_payment_without_targets() {
+    use crate::{
+        card::{
+            abilities::ManaProduction,
+            test_card, test_face,
+            types::{CreatureProperties, LandProperties, Power, Toughness},
+        },
+        mana::payment::{PaymentOptions, find_payment},
+    };
+
+    // Casts the first card in hand, tapping lands for it.
+    struct Caster;
+    impl Agent for Caster {
+        fn choose_action(&mut self, battlefield: &Battlefield, player: PlayerId) -> Action {
+            let Some(card) = battlefield.player(player).zones.hand.first() else {
+                return Action::Pass;
+            };
+            let cost = card.card.faces()[0].mana_cost.as_ref().unwrap();
+            let options = PaymentOptions::default();
+            match find_payment(battlefield, player, cost.as_ref(), &options) {
+                Some(payment) => Action::CastSpell {
+                    card: card.id,
+                    face: CastFace::Face(0),
+                    x: 0,
+                    payment,
+                },
+                None => Action::Pass,
+            }
+        }
+    }
+
+    let forest = test_card(
+        "Forest",
+        Type::Land(LandProperties {
+            is_basic: true,
+            subtypes: Vec::new(),
+        }),
+        vec![Ability::Mana(ManaAbility::tap(ManaProduction::green()))],
+    );
+    let pump = Card::Single(CardFace {
+        mana_cost: Some("{G}".parse().unwrap()),
+        ..test_face(
+            "Pump",
+            Type::Instant,
+            vec![Ability::Spell(Effect::Other {
+                targets: vec![TargetSpec::Creature],
+                resolve: |_, _| {},
+            })],
+        )
+    });
+    let bear = Card::Single(CardFace {
+        mana_cost: Some("{G}".parse().unwrap()),
+        ..test_face(
+            "Bear",
+            Type::Creature(CreatureProperties {
+                subtypes: Vec::new(),
+                power: Power::Value(2),
+                toughness: Toughness::Value(2),
+            }),
+            Vec::new(),
+        )
+    });
+    let mut battlefield = Battlefield::new(vec![PlayerState::new(0, vec![forest, pump, bear])]);
+    battlefield.turn.step = Step::PrecombatMain;
+    let take = |battlefield: &mut Battlefield, name: &str| {
+        let library = &mut battlefield.players[0].zones.library;
+        let i = library.iter().position(|c| c.card.name() == name).unwrap();
+        library.remove(i).unwrap()
+    };
+    let card = take(&mut battlefield, "Forest");
+    battlefield.play_land(card, &mut Caster);
+    let card = take(&mut battlefield, "Pump");
+    battlefield.players[0].zones.hand.push(card);
+
+    // There is no creature to target, so the spell is not cast and the Forest stays untapped.
+    battlefield.play_priority(&mut Caster);
+    assert!(battlefield.objects.iter().all(|o| !o.tapped));
+    assert!(battlefield.players[0].mana_pool.is_empty());
+    assert_eq!(battlefield.players[0].zones.hand.len(), 1);
+    assert!(battlefield.stack.is_empty());
+
+    // With a creature to target, the spell is paid for and resolves.
+    let card = take(&mut battlefield, "Bear");
+    battlefield.objects.push(InPlayObject::new(card));
+    battlefield.play_priority(&mut Caster);
+    assert!(battlefield.players[0].zones.hand.is_empty());
+    assert_eq!(battlefield.players[0].zones.graveyard.len(), 1);
+    assert!(battlefield.objects.iter().any(|o| o.tapped));
+}
+
+#[test]
+fn test_x_spell() {
+    use crate::{
+        card::{
+            abilities::ManaProduction,
+            test_card, test_face,
+            types::{CreatureProperties, LandProperties, Power, Toughness},
+        },
+        mana::payment::{PaymentOptions, find_payment},
+    };
+
+    // Casts the first card in hand with the value of X,
+    // and records the mana value of the spell on the stack.
+    struct XCaster {
+        x: u8,
+        mana_value: Option<u8>,
+    }
+    impl Agent for XCaster {
+        fn choose_action(&mut self, battlefield: &Battlefield, player: PlayerId) -> Action {
+            if let Some(spell) = battlefield.stack.top() {
+                self.mana_value = Some(spell.mana_value());
+            }
+            let Some(card) = battlefield.player(player).zones.hand.first() else {
+                return Action::Pass;
+            };
+            let cost = card.card.faces()[0].mana_cost.as_ref().unwrap();
+            let options = PaymentOptions {
+                x: self.x,
+                ..Default::default()
+            };
+            match find_payment(battlefield, player, cost.as_ref(), &options) {
+                Some(payment) => Action::CastSpell {
+                    card: card.id,
+                    face: CastFace::Face(0),
+                    x: self.x,
+                    payment,
+                },
+                None => Action::Pass,
+            }
+        }
+    }
+
+    let forest = || {
+        test_card(
+            "Forest",
+            Type::Land(LandProperties {
+                is_basic: true,
+                subtypes: Vec::new(),
+            }),
+            vec![Ability::Mana(ManaAbility::tap(ManaProduction::green()))],
+        )
+    };
+    let hydra = Card::Single(CardFace {
+        mana_cost: Some("{X}{G}".parse().unwrap()),
+        ..test_face(
+            "Hydra",
+            Type::Creature(CreatureProperties {
+                subtypes: Vec::new(),
+                power: Power::Value(0),
+                toughness: Toughness::Value(1),
+            }),
+            Vec::new(),
+        )
+    });
+    // Gain X life.
+    let growth = Card::Single(CardFace {
+        mana_cost: Some("{X}{G}".parse().unwrap()),
+        ..test_face(
+            "Growth",
+            Type::Sorcery,
+            vec![Ability::Spell(Effect::Other {
+                targets: Vec::new(),
+                resolve: |battlefield, resolution| {
+                    battlefield.gain_life(resolution.controller, resolution.x.into())
+                },
+            })],
+        )
+    });
+    let mut battlefield = Battlefield::new(vec![PlayerState::new(
+        0,
+        vec![forest(), forest(), forest(), hydra, growth],
+    )]);
+    battlefield.turn.step = Step::PrecombatMain;
+    let library = &mut battlefield.players[0].zones.library;
+    let i = library
+        .iter()
+        .position(|c| c.card.name() == "Growth")
+        .unwrap();
+    let growth = library.remove(i).unwrap();
+    while let Some(card) = battlefield.players[0].zones.library.pop_front() {
+        if card.card.name() == "Hydra" {
+            battlefield.players[0].zones.hand.push(card);
+        } else {
+            battlefield.objects.push(InPlayObject::new(card));
+        }
+    }
+
+    // All three Forests pay for X = 2, and no mana is left in the pool.
+    let mut agent = XCaster {
+        x: 2,
+        mana_value: None,
+    };
+    battlefield.play_priority(&mut agent);
+    assert_eq!(agent.mana_value, Some(3));
+    assert!(battlefield.players[0].zones.hand.is_empty());
+    assert!(battlefield.players[0].mana_pool.is_empty());
+    assert_eq!(battlefield.objects.iter().filter(|o| o.tapped).count(), 3);
+    assert!(
+        battlefield
+            .objects
+            .iter()
+            .any(|o| o.characteristics.name == "Hydra")
+    );
+
+    // The value of X is known when the spell resolves.
+    for object in battlefield.objects.iter_mut() {
+        object.tapped = false;
+    }
+    battlefield.players[0].zones.hand.push(growth);
+    battlefield.play_priority(&mut agent);
+    assert_eq!(battlefield.players[0].life_total, 22);
+    assert_eq!(battlefield.players[0].zones.graveyard.len(), 1);
+}
+
+#[test]
+fn test_damage_and_cleanup() {
+    use crate::{
+        card::{
+            test_card,
+            types::{CreatureProperties, Power, Toughness},
+        },
+        game_play::agent::Goldfish,
+    };
+
+    let bear = test_card(
+        "Bear",
+        Type::Creature(CreatureProperties {
+            subtypes: Vec::new(),
+            power: Power::Value(2),
+            toughness: Toughness::Value(2),
+        }),
+        Vec::new(),
+    );
+    let mut battlefield = Battlefield::new(vec![PlayerState::new(0, vec![bear.clone(), bear])]);
+    let player = battlefield.players[0].id;
+    while let Some(card) = battlefield.players[0].zones.library.pop_front() {
+        battlefield.objects.push(InPlayObject::new(card));
+    }
+    let source = battlefield.objects[0].clone();
+    let target = battlefield.objects[1].id();
+
+    // Non-combat damage to a creature is marked on it.
+    battlefield.deal_damage(&source, Target::Permanent(target), 1, false);
+    assert_eq!(battlefield.object(target).unwrap().damage, 1);
+    assert!(matches!(
+        battlefield.log.last(),
+        Some(Event::Damage {
+            target: Target::Permanent(t),
+            amount: 1,
+            is_combat: false,
+            ..
+        }) if *t == target
+    ));
+
+    // Damage to a player causes them to lose life.
+    battlefield.deal_damage(&source, Target::Player(player), 3, false);
+    assert_eq!(battlefield.players[0].life_total, 17);
+    assert!(matches!(
+        battlefield.log.last(),
+        Some(Event::LostLife(p, 3)) if *p == player
+    ));
+
+    // Damage wears off in the cleanup step.
+    battlefield.turn.step = Step::End;
+    assert_eq!(battlefield.advance_step(&mut Goldfish), Some(Step::Cleanup));
+    assert_eq!(battlefield.object(target).unwrap().damage, 0);
+
+    // Lethal damage destroys the creature when state-based actions are checked.
+    battlefield.deal_damage(&source, Target::Permanent(target), 2, false);
+    assert!(battlefield.object(target).is_some());
+    battlefield.check_state_based_actions(&mut Goldfish);
+    assert!(battlefield.object(target).is_none());
+    assert_eq!(battlefield.players[0].zones.graveyard.len(), 1);
+    assert!(matches!(battlefield.log.last(), Some(Event::Died(_))));
+}
+
+#[test]
+fn test_zone_changes() {
+    use crate::{
+        card::{
+            test_face,
+            types::{CreatureProperties, Power, Toughness},
+        },
+        game_play::agent::Goldfish,
+    };
+
+    let bear = Card::Single(CardFace {
+        mana_cost: Some("{G}".parse().unwrap()),
+        ..test_face(
+            "Bear",
+            Type::Creature(CreatureProperties {
+                subtypes: Vec::new(),
+                power: Power::Value(2),
+                toughness: Toughness::Value(2),
+            }),
+            Vec::new(),
+        )
+    });
+    let murder = Card::Single(CardFace {
+        mana_cost: Some("{B}".parse().unwrap()),
+        ..test_face(
+            "Murder",
+            Type::Instant,
+            vec![Ability::Spell(Effect::Other {
+                targets: vec![TargetSpec::Creature],
+                resolve: |battlefield, resolution| {
+                    if let Some(id) = resolution.target_permanent(0) {
+                        battlefield.destroy(id);
+                    }
+                },
+            })],
+        )
+    });
+    let deck = vec![bear.clone(), bear, murder];
+    let mut battlefield = Battlefield::new(vec![PlayerState::new(0, deck)]);
+    let mut take = |name: &str| {
+        let library = &mut battlefield.players[0].zones.library;
+        let i = library.iter().position(|c| c.card.name() == name).unwrap();
+        library.remove(i).unwrap()
+    };
+    let (bear, other_bear, murder) = (take("Bear"), take("Bear"), take("Murder"));
+    assert_ne!(
+        bear.id, other_bear.id,
+        "Copies of a card are different objects"
+    );
+    let player = battlefield.players[0].id;
+
+    // Hand -> stack -> battlefield -> graveyard, with a new id each time.
+    let in_hand = bear.id;
+    battlefield
+        .cast_spell(bear, CastFace::Face(0), &mut Goldfish)
+        .unwrap();
+    let on_stack = match &battlefield.stack.top().unwrap().kind {
+        StackObjectKind::Spell { card, .. } => card.id,
+        kind => panic!("Unexpected {kind:?}"),
+    };
+    battlefield.resolve_stack(&mut Goldfish);
+    let on_battlefield = battlefield.objects[0].id();
+    battlefield.destroy(on_battlefield);
+    let in_graveyard = battlefield.players[0].zones.graveyard[0].id;
+    let ids = [in_hand, on_stack, on_battlefield, in_graveyard];
+    assert!(ids.iter().enumerate().all(|(i, id)| !ids[..i].contains(id)));
+
+    // A spell targets the creature, which then leaves and returns to the battlefield.
+    let card = battlefield.players[0].zones.graveyard.pop().unwrap();
+    battlefield
+        .objects
+        .push(InPlayObject::new(card.change_zone()));
+    let target = battlefield.objects[0].id();
+    battlefield
+        .cast_spell(murder, CastFace::Face(0), &mut Goldfish)
+        .unwrap();
+    assert_eq!(
+        battlefield.stack.top().unwrap().targets,
+        vec![Target::Permanent(target)]
+    );
+    let card = battlefield.objects.pop().unwrap().card;
+    battlefield
+        .objects
+        .push(InPlayObject::new(card.change_zone()));
+    assert!(!battlefield.is_legal_target(player, TargetSpec::Creature, Target::Permanent(target)));
+
+    // The returned creature is a new object, so the spell does not destroy it.
+    battlefield.resolve_stack(&mut Goldfish);
+    assert_eq!(battlefield.objects.len(), 1);
+    assert!(battlefield.object(target).is_none());
+}
@@ -0,0 +1,549 @@
+use crate::{
+    card::abilities::Ability,
+    game_play::{
+        ObjectId,
+        agent::Agent,
+        battlefield::{Battlefield, Event, InPlayObject},
+        player::PlayerId,
+        target::Target,
+        turn::Step,
+    },
+};
+
+/// The creatures in combat this turn. It is cleared once combat is over.
+#[derive(Debug, Clone, Default)]
+pub struct Combat {
+    pub attackers: Vec<Attacker>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Attacker {
+    pub object: ObjectId,
+    pub defender: Defender,
+    // A creature stays blocked even if its blockers leave combat.
+    pub blocked: bool,
+    // In the order damage is assigned to them.
+    pub blockers: Vec<ObjectId>,
+}
+
+// What an attacking creature attacks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Defender {
+    Player(PlayerId),
+    // A planeswalker or battle.
+    Permanent(ObjectId),
+}
+
+impl Defender {
+    pub fn target(&self) -> Target {
+        match self {
+            Self::Player(player) => Target::Player(*player),
+            Self::Permanent(id) => Target::Permanent(*id),
+        }
+    }
+}
+
+impl Combat {
+    pub fn is_attacking(&self, object: ObjectId) -> bool {
+        self.attackers.iter().any(|a| a.object == object)
+    }
+
+    pub fn is_blocking(&self, object: ObjectId) -> bool {
+        self.attackers.iter().any(|a| a.blockers.contains(&object))
+    }
+
+    fn attacker_mut(&mut self, object: ObjectId) -> Option<&mut Attacker> {
+        self.attackers.iter_mut().find(|a| a.object == object)
+    }
+
+    // Removes the object from combat as it leaves the battlefield.
+    pub(crate) fn remove(&mut self, object: ObjectId) {
+        self.attackers.retain(|a| a.object != object);
+        for attacker in self.attackers.iter_mut() {
+            attacker.blockers.retain(|b| *b != object);
+        }
+    }
+}
+
+impl Battlefield {
+    // An untapped creature controlled by the active player since the start of their turn
+    // (unless it has haste) can attack an opponent, a planeswalker an opponent controls,
+    // or a battle the active player does not protect.
+    pub fn can_attack(&self, object: ObjectId, defender: Defender) -> bool {
+        let attacking_player = self.turn.active_player;
+        self.object(object).is_some_and(|o| {
+            o.controller == attacking_player
+                && o.is_creature()
+                && !o.tapped
+                && !o.has_summoning_sickness()
+        }) && self
+            .defending_player(defender)
+            .is_some_and(|p| p != attacking_player)
+    }
+
+    // The player who can block creatures attacking the `defender`:
+    // the controller of a planeswalker, or the protector of a battle.
+    pub fn defending_player(&self, defender: Defender) -> Option<PlayerId> {
+        match defender {
+            Defender::Player(player) => self
+                .players
+                .iter()
+                .any(|p| p.id == player)
+                .then_some(player),
+            Defender::Permanent(id) => {
+                let object = self.object(id)?;
+                if object.is_battle() {
+                    object.protector
+                } else if object.is_planeswalker() {
+                    Some(object.controller)
+                } else {
+                    None
+                }
+            }
+        }
+    }
+
+    // Whether the creature `blocker` can block the attacking creature `attacker`.
+    // Menace is only checked once all blockers are declared.
+    pub fn can_block(&self, blocker: ObjectId, attacker: ObjectId) -> bool {
+        let Some(attack) = self.combat.attackers.iter().find(|a| a.object == attacker) else {
+            return false;
+        };
+        let (Some(blocker), Some(attacker)) = (self.object(blocker), self.object(attacker)) else {
+            return false;
+        };
+        let has_evasion = attacker.has_ability(|a| matches!(a, Ability::Flying))
+            && !blocker.has_ability(|a| matches!(a, Ability::Flying | Ability::Reach));
+        self.defending_player(attack.defender) == Some(blocker.controller)
+            && blocker.is_creature()
+            && !blocker.tapped
+            && !has_evasion
+    }
+
+    // The active player declares attackers chosen by the `agent`.
+    // Attacking creatures become tapped (unless they have vigilance).
+    // Illegal choices are ignored.
+    pub(crate) fn declare_attackers(&mut self, agent: &mut dyn Agent) {
+        let player = self.turn.active_player;
+        self.combat.attackers.clear();
+        for (object, defender) in agent.choose_attackers(self, player) {
+            if self.combat.is_attacking(object) || !self.can_attack(object, defender) {
+                continue;
+            }
+            self.combat.attackers.push(Attacker {
+                object,
+                defender,
+                blocked: false,
+                blockers: Vec::new(),
+            });
+        }
+
+        let attackers: Vec<ObjectId> = self.combat.attackers.iter().map(|a| a.object).collect();
+        for object in attackers {
+            let has_vigilance = self
+                .object(object)
+                .is_some_and(|o| o.has_ability(|a| matches!(a, Ability::Vigilance)));
+            if !has_vigilance {
+                self.tap(object);
+            }
+            self.log_event(Event::Attacked(object));
+        }
+    }
+
+    // Each defending player declares blockers chosen by the `agent`.
+    // Illegal choices are ignored, as are all blocks of an attacker with menace
+    // by a single creature.
+    pub(crate) fn declare_blockers(&mut self, agent: &mut dyn Agent) {
+        let mut defenders: Vec<PlayerId> = Vec::new();
+        for attacker in &self.combat.attackers {
+            if let Some(defender) = self.defending_player(attacker.defender)
+                && !defenders.contains(&defender)
+            {
+                defenders.push(defender);
+            }
+        }
+        for defender in defenders {
+            for (blocker, attacker) in agent.choose_blockers(self, defender) {
+                // TODO: creatures which can block more than one attacker.
+                if self.combat.is_blocking(blocker)
+                    || self.object(blocker).map(|o| o.controller) != Some(defender)
+                    || !self.can_block(blocker, attacker)
+                {
+                    continue;
+                }
+                if let Some(attacker) = self.combat.attacker_mut(attacker) {
+                    attacker.blockers.push(blocker);
+                }
+            }
+        }
+
+        let mut blocks = Vec::new();
+        for attacker in self.combat.attackers.iter_mut() {
+            let has_menace = self
+                .objects
+                .iter()
+                .find(|o| o.id() == attacker.object)
+                .is_some_and(|o| o.has_ability(|a| matches!(a, Ability::Menace)));
+            if attacker.blockers.len() == 1 && has_menace {
+                attacker.blockers.clear();
+            }
+            attacker.blocked = !attacker.blockers.is_empty();
+            for blocker in &attacker.blockers {
+                blocks.push(Event::Blocked {
+                    blocker: *blocker,
+                    attacker: attacker.object,
+                });
+            }
+        }
+        for event in blocks {
+            self.log_event(event);
+        }
+    }
+
+    // Attacking and blocking creatures deal damage simultaneously. In the first strike damage
+    // step only creatures with first strike or double strike deal damage, and in the
+    // regular step only those without first strike (or with double strike).
+    // An attacker assigns lethal damage to each blocker in order before moving on to the next;
+    // with trample, the rest is assigned to the defending player.
+    pub(crate) fn combat_damage(&mut self, first_strike: bool) {
+        let deals_damage = |object: &InPlayObject| {
+            let has_double_strike = object.has_ability(|a| matches!(a, Ability::DoubleStrike));
+            let has_first_strike = object.has_ability(|a| matches!(a, Ability::FirstStrike));
+            has_double_strike || has_first_strike == first_strike
+        };
+        // (source, target, amount)
+        let mut assignments: Vec<(ObjectId, Target, u32)> = Vec::new();
+        for attacker in &self.combat.attackers {
+            if let Some(object) = self.object(attacker.object)
+                && deals_damage(object)
+            {
+                let mut remaining = object.power();
+                if !attacker.blocked {
+                    assignments.push((attacker.object, attacker.defender.target(), remaining));
+                }
+                // TODO: the attacking player could choose to assign more than lethal damage.
+                let tramples = object.has_ability(|a| matches!(a, Ability::Trample));
+                for (i, blocker) in attacker.blockers.iter().enumerate() {
+                    let is_last = i + 1 == attacker.blockers.len();
+                    let lethal = self.object(*blocker).map_or(0, |o| o.lethal_damage());
+                    let amount = if is_last && !tramples {
+                        remaining
+                    } else {
+                        remaining.min(lethal)
+                    };
+                    assignments.push((attacker.object, Target::Permanent(*blocker), amount));
+                    remaining -= amount;
+                }
+                if attacker.blocked && tramples {
+                    assignments.push((attacker.object, attacker.defender.target(), remaining));
+                }
+            }
+            for blocker in &attacker.blockers {
+                if let Some(blocking) = self.object(*blocker)
+                    && deals_damage(blocking)
+                {
+                    assignments.push((
+                        *blocker,
+                        Target::Permanent(attacker.object),
+                        blocking.power(),
+                    ));
+                }
+            }
+        }
+
+        for (source, target, amount) in assignments {
+            if let Some(source) = self.object(source).cloned() {
+                self.deal_damage(&source, target, amount, true);
+            }
+        }
+    }
+
+    // The blocking and damage steps are skipped if no creatures attack,
+    // and the first strike damage step if no creature in combat has first or double strike.
+    pub(crate) fn skips_step(&self, step: Step) -> bool {
+        match step {
+            Step::DeclareBlockers | Step::CombatDamage => self.combat.attackers.is_empty(),
+            Step::FirstStrikeDamage => !self
+                .combat
+                .attackers
+                .iter()
+                .flat_map(|a| std::iter::once(&a.object).chain(&a.blockers))
+                .filter_map(|object| self.object(*object))
+                .any(|o| {
+                    o.has_ability(|a| matches!(a, Ability::FirstStrike | Ability::DoubleStrike))
+                }),
+            _ => false,
+        }
+    }
+}
+
+#[test]
+fn test_combat() {
+    use crate::card::{
+        Card, test_card,
+        types::{CreatureProperties, Power, Toughness, Type},
+    };
+    use crate::game_play::player::PlayerState;
+
+    // Attacks with everything, and blocks each attacker with every creature.
+    struct AllIn;
+    impl Agent for AllIn {
+        fn choose_attackers(
+            &mut self,
+            battlefield: &Battlefield,
+            player: PlayerId,
+        ) -> Vec<(ObjectId, Defender)> {
+            let defender = Defender::Player(battlefield.next_player(player));
+            battlefield
+                .objects
+                .iter()
+                .map(|o| (o.id(), defender))
+                .collect()
+        }
+
+        fn choose_blockers(
+            &mut self,
+            battlefield: &Battlefield,
+            player: PlayerId,
+        ) -> Vec<(ObjectId, ObjectId)> {
+            let blockers = battlefield
+                .objects
+                .iter()
+                .filter(|o| o.controller == player);
+            blockers
+                .map(|o| o.id())
+                .flat_map(|b| {
+                    battlefield
+                        .combat
+                        .attackers
+                        .iter()
+                        .map(move |a| (b, a.object))
+                })
+                .collect()
+        }
+    }
+
+    let creature = |name: &str, power: u32, toughness: u32, abilities: Vec<Ability>| {
+        let creature = Type::Creature(CreatureProperties {
+            subtypes: Vec::new(),
+            power: Power::Value(power),
+            toughness: Toughness::Value(toughness),
+        });
+        test_card(name, creature, abilities)
+    };
+    let fight = |attackers: Vec<Card>, blockers: Vec<Card>| {
+        let mut battlefield = Battlefield::new(vec![
+            PlayerState::new(0, attackers),
+            PlayerState::new(1, blockers),
+        ]);
+        for player in battlefield.players.clone() {
+            for card in player.zones.library {
+                battlefield.objects.push(InPlayObject::new(card));
+            }
+        }
+        battlefield.start_turn(battlefield.players[0].id);
+        battlefield.turn.step = Step::BeginningOfCombat;
+        while battlefield.turn.step != Step::EndOfCombat {
+            battlefield.advance_step(&mut AllIn);
+            battlefield.play_priority(&mut AllIn);
+        }
+        battlefield
+    };
+
+    // Trample assigns lethal damage to the blocker and the rest to the player,
+    // while lifelink gains life for all of it.
+    let battlefield = fight(
+        vec![creature(
+            "Wurm",
+            5,
+            5,
+            vec![Ability::Trample, Ability::Lifelink],
+        )],
+        vec![creature("Bear", 2, 2, Vec::new())],
+    );
+    assert!(battlefield.objects[0].tapped);
+    assert_eq!(battlefield.players[1].life_total, 17);
+    assert_eq!(battlefield.players[0].life_total, 25);
+
+    // Flying attackers cannot be blocked without flying or reach.
+    let battlefield = fight(
+        vec![creature("Bird", 1, 1, vec![Ability::Flying])],
+        vec![creature("Bear", 2, 2, Vec::new())],
+    );
+    assert_eq!(battlefield.players[1].life_total, 19);
+    let battlefield = fight(
+        vec![creature("Bird", 1, 1, vec![Ability::Flying])],
+        vec![creature("Spider", 1, 3, vec![Ability::Reach])],
+    );
+    assert_eq!(battlefield.players[1].life_total, 20);
+
+    // Menace requires two blockers.
+    let battlefield = fight(
+        vec![creature("Goblin", 2, 2, vec![Ability::Menace])],
+        vec![creature("Bear", 2, 2, Vec::new())],
+    );
+    assert_eq!(battlefield.players[1].life_total, 18);
+
+    // The first striker destroys the blocker before it can deal damage,
+    // and (without trample) deals no damage to the player once its blocker is gone.
+    let battlefield = fight(
+        vec![creature("Knight", 2, 2, vec![Ability::DoubleStrike])],
+        vec![creature("Bear", 2, 2, Vec::new())],
+    );
+    let damage: Vec<(ObjectId, Step)> = {
+        let mut step = Step::Untap;
+        let mut damage = Vec::new();
+        for event in &battlefield.log {
+            match event {
+                Event::BeginStep(s) => step = *s,
+                Event::Damage { source, .. } => damage.push((*source, step)),
+                _ => (),
+            }
+        }
+        damage
+    };
+    let knight = battlefield.objects[0].id();
+    assert_eq!(damage, vec![(knight, Step::FirstStrikeDamage)]);
+    assert_eq!(battlefield.objects.len(), 1);
+    assert_eq!(battlefield.players[1].zones.graveyard.len(), 1);
+    assert_eq!(battlefield.players[1].life_total, 20);
+}
+
+#[test]
+fn test_planeswalkers_and_battles() {
+    use crate::{
+        card::{
+            abilities::{AbilityCost, ActivatedAbility, Effect},
+            test_card,
+            types::{
+                BattleProperties, BattleSubtypes, CreatureProperties, PlaneswalkerProperties,
+                Power, Toughness, Type,
+            },
+        },
+        game_play::{agent::Action, counters::CounterKind, player::PlayerState},
+        mana::payment::PaymentPlan,
+    };
+
+    // Attacks the planeswalker with the first creature and the battle with the second,
+    // and activates the planeswalker's loyalty ability.
+    struct Superfriends(usize);
+    impl Agent for Superfriends {
+        fn choose_attackers(
+            &mut self,
+            battlefield: &Battlefield,
+            _: PlayerId,
+        ) -> Vec<(ObjectId, Defender)> {
+            let find = |f: fn(&InPlayObject) -> bool| {
+                battlefield
+                    .objects
+                    .iter()
+                    .filter(move |o| f(o))
+                    .map(|o| o.id())
+            };
+            let defenders =
+                find(InPlayObject::is_planeswalker).chain(find(InPlayObject::is_battle));
+            find(InPlayObject::is_creature)
+                .zip(defenders.map(Defender::Permanent))
+                .collect()
+        }
+
+        fn choose_action(&mut self, battlefield: &Battlefield, player: PlayerId) -> Action {
+            match battlefield.objects.iter().find(|o| o.is_planeswalker()) {
+                Some(planeswalker) if planeswalker.controller == player => {
+                    Action::ActivateAbility {
+                        source: planeswalker.id(),
+                        ability: self.0,
+                        payment: PaymentPlan::default(),
+                    }
+                }
+                _ => Action::Pass,
+            }
+        }
+    }
+
+    let creature = |name: &str, power: u32| {
+        let creature = Type::Creature(CreatureProperties {
+            subtypes: Vec::new(),
+            power: Power::Value(power),
+            toughness: Toughness::Value(power),
+        });
+        test_card(name, creature, Vec::new())
+    };
+    let loyalty = |loyalty: i32, effect: Effect| {
+        Ability::Activated(ActivatedAbility {
+            cost: AbilityCost {
+                loyalty: Some(loyalty),
+                ..Default::default()
+            },
+            effect,
+            sorcery_speed: false,
+        })
+    };
+    let planeswalker = test_card(
+        "Walker",
+        Type::Planeswalker(PlaneswalkerProperties { loyalty: 3 }),
+        vec![
+            loyalty(1, Effect::GainLife { amount: 1 }),
+            loyalty(-3, Effect::Draw { amount: 1 }),
+        ],
+    );
+    let siege = test_card(
+        "Siege",
+        Type::Battle(BattleProperties {
+            subtypes: vec![BattleSubtypes::Siege],
+            defense: 3,
+        }),
+        Vec::new(),
+    );
+
+    let mut battlefield = Battlefield::new(vec![
+        PlayerState::new(0, vec![creature("Bear", 2), creature("Giant", 3), siege]),
+        PlayerState::new(1, vec![planeswalker]),
+    ]);
+    let opponent = battlefield.players[1].id;
+    for player in battlefield.players.clone() {
+        for card in player.zones.library {
+            battlefield.objects.push(InPlayObject::new(card));
+        }
+    }
+    battlefield.objects.sort_by_key(|o| o.card.card.name());
+    for siege in battlefield.objects.iter_mut().filter(|o| o.is_siege()) {
+        siege.protector = Some(opponent);
+    }
+    let walker = battlefield.objects.iter().find(|o| o.is_planeswalker());
+    let walker = walker.unwrap().id();
+    assert_eq!(
+        battlefield
+            .object(walker)
+            .unwrap()
+            .count_counters(&CounterKind::Loyalty),
+        3
+    );
+
+    // Combat damage removes loyalty and defense counters; the defeated battle is put
+    // into the graveyard.
+    battlefield.start_turn(battlefield.players[0].id);
+    battlefield.turn.step = Step::BeginningOfCombat;
+    while battlefield.turn.step != Step::EndOfCombat {
+        battlefield.advance_step(&mut Superfriends(0));
+        battlefield.play_priority(&mut Superfriends(0));
+    }
+    let loyalty = |battlefield: &Battlefield| {
+        let walker = battlefield.object(walker).unwrap();
+        walker.count_counters(&CounterKind::Loyalty)
+    };
+    assert_eq!(loyalty(&battlefield), 1);
+    assert!(!battlefield.objects.iter().any(|o| o.is_battle()));
+    assert_eq!(battlefield.players[0].zones.graveyard.len(), 1);
+    assert_eq!(battlefield.players[1].life_total, 20);
+
+    // A loyalty ability is activated at most once per turn, and only if its cost can be paid.
+    battlefield.start_turn(opponent);
+    battlefield.turn.step = Step::PrecombatMain;
+    battlefield.play_priority(&mut Superfriends(1));
+    assert_eq!(loyalty(&battlefield), 1);
+    battlefield.play_priority(&mut Superfriends(0));
+    battlefield.play_priority(&mut Superfriends(0));
+    assert_eq!(loyalty(&battlefield), 2);
+    assert_eq!(battlefield.players[1].life_total, 21);
+}
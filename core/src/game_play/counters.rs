@@ -1,6 +1,62 @@
-#[derive(Debug, Clone)]
+use crate::{
+    card::abilities::Ability,
+    game_play::{
+        battlefield::{Battlefield, Event},
+        target::Target,
+    },
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum CounterKind {
-    // TODO: +1/+1, -1/-1, ability counters, lore counters, etc.
+    // A +1/+1 and a -1/-1 counter on the same permanent are removed as a state-based action.
+    PlusOnePlusOne,
+    MinusOneMinusOne,
+    // Planeswalkers enter with loyalty counters, and battles with defense counters.
+    // Damage dealt to them removes that many.
+    Loyalty,
+    Defense,
+    // TODO: Sagas
+    Lore,
+    // Removed instead of the permanent untapping.
+    Stun,
+    // Removed instead of the permanent being dealt damage or destroyed.
+    Shield,
+    // The permanent has the ability (e.g. a flying counter).
+    Keyword(KeywordCounter),
+    Poison,
+    Energy,
+    Experience,
+    // Counters without rules of their own (e.g. oil or time counters).
+    Other(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeywordCounter {
+    Flying,
+    Trample,
+    Menace,
+    Lifelink,
+    FirstStrike,
+    DoubleStrike,
+    Reach,
+    Vigilance,
+    Haste,
+}
+
+impl KeywordCounter {
+    pub fn ability(&self) -> Ability {
+        match self {
+            Self::Flying => Ability::Flying,
+            Self::Trample => Ability::Trample,
+            Self::Menace => Ability::Menace,
+            Self::Lifelink => Ability::Lifelink,
+            Self::FirstStrike => Ability::FirstStrike,
+            Self::DoubleStrike => Ability::DoubleStrike,
+            Self::Reach => Ability::Reach,
+            Self::Vigilance => Ability::Vigilance,
+            Self::Haste => Ability::Haste,
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -8,3 +64,142 @@ pub struct Counters {
     pub kind: CounterKind,
     pub amount: u32,
 }
+
+// Number of counters of the kind.
+pub fn count(counters: &[Counters], kind: &CounterKind) -> u32 {
+    counters
+        .iter()
+        .find(|c| c.kind == *kind)
+        .map_or(0, |c| c.amount)
+}
+
+fn add(counters: &mut Vec<Counters>, kind: CounterKind, amount: u32) {
+    match counters.iter_mut().find(|c| c.kind == kind) {
+        Some(c) => c.amount += amount,
+        None => counters.push(Counters { kind, amount }),
+    }
+}
+
+// Returns the number of counters actually removed.
+fn remove(counters: &mut Vec<Counters>, kind: &CounterKind, amount: u32) -> u32 {
+    let Some(index) = counters.iter().position(|c| c.kind == *kind) else {
+        return 0;
+    };
+    let removed = amount.min(counters[index].amount);
+    counters[index].amount -= removed;
+    if counters[index].amount == 0 {
+        counters.remove(index);
+    }
+    removed
+}
+
+impl Battlefield {
+    // Puts counters on a permanent or gives them to a player.
+    // Does nothing if the permanent is no longer on the battlefield.
+    pub fn add_counters(&mut self, target: Target, kind: CounterKind, amount: u32) {
+        if amount == 0 {
+            return;
+        }
+        let Some(counters) = self.counters_mut(target) else {
+            return;
+        };
+        add(counters, kind.clone(), amount);
+        // Counters can change power and toughness, and abilities.
+        if let Target::Permanent(_) = target {
+            self.apply_continuous_effects();
+        }
+        self.log_event(Event::AddedCounters {
+            target,
+            kind,
+            amount,
+        });
+    }
+
+    // Returns the number of counters removed (there may have been fewer than `amount`).
+    pub fn remove_counters(&mut self, target: Target, kind: CounterKind, amount: u32) -> u32 {
+        let Some(counters) = self.counters_mut(target) else {
+            return 0;
+        };
+        let removed = remove(counters, &kind, amount);
+        if removed == 0 {
+            return 0;
+        }
+        if let Target::Permanent(_) = target {
+            self.apply_continuous_effects();
+        }
+        self.log_event(Event::RemovedCounters {
+            target,
+            kind,
+            amount: removed,
+        });
+        removed
+    }
+
+    fn counters_mut(&mut self, target: Target) -> Option<&mut Vec<Counters>> {
+        match target {
+            Target::Player(player) => Some(&mut self.player_mut(player).counters),
+            Target::Permanent(id) => self.object_mut(id).map(|o| &mut o.counters),
+            Target::CardInGraveyard(_) => None,
+        }
+    }
+}
+
+#[test]
+fn test_counters() {
+    use crate::{
+        card::{
+            test_card,
+            types::{CreatureProperties, Power, Toughness, Type},
+        },
+        game_play::{agent::Goldfish, battlefield::InPlayObject, player::PlayerState},
+    };
+
+    let creature = Type::Creature(CreatureProperties {
+        subtypes: Vec::new(),
+        power: Power::Value(2),
+        toughness: Toughness::Value(2),
+    });
+    let bear = test_card("Bear", creature, Vec::new());
+    let mut battlefield = Battlefield::new(vec![
+        PlayerState::new(0, vec![bear]),
+        PlayerState::new(1, Vec::new()),
+    ]);
+    let card = battlefield.players[0].zones.library.pop_front().unwrap();
+    battlefield.objects.push(InPlayObject::new(card));
+    let bear = Target::Permanent(battlefield.objects[0].id());
+
+    // +1/+1 and -1/-1 counters cancel out.
+    battlefield.add_counters(bear, CounterKind::PlusOnePlusOne, 2);
+    battlefield.add_counters(bear, CounterKind::MinusOneMinusOne, 1);
+    battlefield.add_counters(bear, CounterKind::Keyword(KeywordCounter::Flying), 1);
+    battlefield.check_state_based_actions(&mut Goldfish);
+    let object = &battlefield.objects[0];
+    assert_eq!(count(&object.counters, &CounterKind::PlusOnePlusOne), 1);
+    assert_eq!(count(&object.counters, &CounterKind::MinusOneMinusOne), 0);
+    assert_eq!((object.power(), object.toughness()), (3, 3));
+    assert!(object.has_ability(|a| matches!(a, Ability::Flying)));
+
+    // The shield counter is removed instead of the damage being dealt.
+    battlefield.add_counters(bear, CounterKind::Shield, 1);
+    let source = battlefield.objects[0].clone();
+    battlefield.deal_damage(&source, bear, 3, false);
+    battlefield.check_state_based_actions(&mut Goldfish);
+    assert_eq!(battlefield.objects.len(), 1);
+    assert!(battlefield.objects[0].counters.len() == 2);
+
+    // The stun counter is removed instead of untapping.
+    battlefield.objects[0].tapped = true;
+    battlefield.add_counters(bear, CounterKind::Stun, 1);
+    let player = battlefield.players[0].id;
+    battlefield.start_turn(player);
+    assert!(battlefield.objects[0].tapped);
+    battlefield.start_turn(player);
+    assert!(!battlefield.objects[0].tapped);
+
+    let opponent = Target::Player(battlefield.players[1].id);
+    battlefield.add_counters(opponent, CounterKind::Poison, 10);
+    battlefield.check_state_based_actions(&mut Goldfish);
+    assert!(battlefield.players[1].has_lost);
+    let removed = |e: &&Event| matches!(e, Event::RemovedCounters { .. });
+    assert_eq!(battlefield.log.iter().filter(removed).count(), 4);
+}
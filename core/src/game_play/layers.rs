@@ -0,0 +1,439 @@
+use {
+    crate::{
+        card::{
+            CardFace,
+            abilities::{Ability, PermanentFilter},
+            color::Color,
+            types::Type,
+        },
+        game_play::{
+            ObjectId,
+            battlefield::{Battlefield, InPlayObject},
+            counters::CounterKind,
+            player::PlayerId,
+        },
+    },
+    std::sync::atomic::{AtomicU64, Ordering},
+};
+
+/// Continuous effects which apply in the same layer are applied in timestamp order.
+/// A permanent gets a timestamp as it enters the battlefield (which is also
+/// the timestamp of the effects from its static abilities).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Timestamp(u64);
+
+impl Timestamp {
+    pub fn next() -> Self {
+        static NEXT: AtomicU64 = AtomicU64::new(0);
+        Self(NEXT.fetch_add(1, Ordering::Relaxed))
+    }
+}
+
+/// The layers in which continuous effects are applied, in order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Layer {
+    Copy,
+    Control,
+    // TODO: there are no text-changing effects yet.
+    Text,
+    Type,
+    Color,
+    Ability,
+    // Characteristic-defining abilities (7a) are part of the printed power and toughness.
+    SetPowerToughness,
+    ModifyPowerToughness,
+    SwitchPowerToughness,
+}
+
+impl Layer {
+    pub const ALL: [Self; 9] = [
+        Self::Copy,
+        Self::Control,
+        Self::Text,
+        Self::Type,
+        Self::Color,
+        Self::Ability,
+        Self::SetPowerToughness,
+        Self::ModifyPowerToughness,
+        Self::SwitchPowerToughness,
+    ];
+}
+
+#[derive(Debug, Clone)]
+pub enum Modification {
+    // The copiable values become those of the face (e.g. "enters as a copy of").
+    Copy(Box<CardFace>),
+    Control(PlayerId),
+    // Replaces the type line(s), e.g. "becomes a 4/4 artifact creature".
+    SetType(Type),
+    SetColor(Color),
+    AddAbility(Box<Ability>),
+    LoseAllAbilities,
+    SetPowerToughness { power: i32, toughness: i32 },
+    // E.g. "+2/+2 until end of turn" or "creatures you control get +1/+1".
+    ModifyPowerToughness { power: i32, toughness: i32 },
+    SwitchPowerToughness,
+}
+
+impl Modification {
+    pub fn layer(&self) -> Layer {
+        match self {
+            Self::Copy(_) => Layer::Copy,
+            Self::Control(_) => Layer::Control,
+            Self::SetType(_) => Layer::Type,
+            Self::SetColor(_) => Layer::Color,
+            Self::AddAbility(_) | Self::LoseAllAbilities => Layer::Ability,
+            Self::SetPowerToughness { .. } => Layer::SetPowerToughness,
+            Self::ModifyPowerToughness { .. } => Layer::ModifyPowerToughness,
+            Self::SwitchPowerToughness => Layer::SwitchPowerToughness,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Duration {
+    EndOfTurn,
+    // Until the affected permanents leave the battlefield.
+    Indefinite,
+}
+
+#[derive(Debug, Clone)]
+pub enum Affected {
+    // Effects from spells and abilities which resolved only affect
+    // the objects determined at that time.
+    Objects(Vec<ObjectId>),
+    // Every permanent matching the filter at the time the effect is applied.
+    Permanents {
+        controller: Option<PlayerId>,
+        filter: PermanentFilter,
+        except: Option<ObjectId>,
+    },
+}
+
+impl Affected {
+    pub fn contains(&self, object: &InPlayObject) -> bool {
+        match self {
+            Self::Objects(ids) => ids.contains(&object.id()),
+            Self::Permanents {
+                controller,
+                filter,
+                except,
+            } => {
+                controller.is_none_or(|c| c == object.controller)
+                    && *except != Some(object.id())
+                    && filter.matches(object)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ContinuousEffect {
+    pub timestamp: Timestamp,
+    pub affected: Affected,
+    pub modification: Modification,
+    pub duration: Duration,
+}
+
+/// The characteristics of a permanent after continuous effects are applied.
+#[derive(Debug, Clone)]
+pub struct Characteristics {
+    pub name: String,
+    pub color: Color,
+    pub is_legendary: bool,
+    pub is_snow: bool,
+    // One for each face
+    pub type_lines: Vec<Type>,
+    pub abilities: Vec<Ability>,
+    // `None` for non-creatures.
+    // TODO: dynamic power and toughness (e.g. */*+1) are also `None`
+    // (so creatures with dynamic toughness are not put into the graveyard for it).
+    pub power: Option<i32>,
+    pub toughness: Option<i32>,
+}
+
+impl Characteristics {
+    pub fn printed(faces: &[&CardFace]) -> Self {
+        let names: Vec<&str> = faces.iter().map(|f| f.name.as_str()).collect();
+        let mut characteristics = Self {
+            name: names.join(" // "),
+            color: faces
+                .iter()
+                .fold(Color::colorless(), |color, f| color.and(f.color.clone())),
+            is_legendary: faces.iter().any(|f| f.is_legendary),
+            is_snow: faces.iter().any(|f| f.is_snow),
+            type_lines: faces.iter().map(|f| f.type_line.clone()).collect(),
+            abilities: faces.iter().flat_map(|f| f.abilities.clone()).collect(),
+            power: None,
+            toughness: None,
+        };
+        characteristics.set_base_power_toughness();
+        characteristics
+    }
+
+    fn set_base_power_toughness(&mut self) {
+        use crate::card::types::{Power, Toughness};
+
+        let creature = self.type_lines.iter().find_map(Type::creature);
+        self.power = creature.and_then(|c| match c.power {
+            Power::Value(power) => Some(power as i32),
+            Power::Dynamic { .. } => None,
+        });
+        self.toughness = creature.and_then(|c| match c.toughness {
+            Toughness::Value(toughness) => Some(toughness as i32),
+            Toughness::Dynamic { .. } => None,
+        });
+    }
+
+    // Control is not a characteristic, so it is handled by `Battlefield::apply_continuous_effects`.
+    fn apply(&mut self, modification: &Modification) {
+        match modification {
+            Modification::Copy(face) => *self = Self::printed(&[face]),
+            Modification::Control(_) => (),
+            Modification::SetType(type_line) => {
+                self.type_lines = vec![type_line.clone()];
+                self.set_base_power_toughness();
+            }
+            Modification::SetColor(color) => self.color = color.clone(),
+            Modification::AddAbility(ability) => self.abilities.push((**ability).clone()),
+            Modification::LoseAllAbilities => self.abilities.clear(),
+            Modification::SetPowerToughness { power, toughness } => {
+                if self.type_lines.iter().any(|t| t.creature().is_some()) {
+                    self.power = Some(*power);
+                    self.toughness = Some(*toughness);
+                }
+            }
+            Modification::ModifyPowerToughness { power, toughness } => {
+                self.power = self.power.map(|p| p + power);
+                self.toughness = self.toughness.map(|t| t + toughness);
+            }
+            Modification::SwitchPowerToughness => {
+                std::mem::swap(&mut self.power, &mut self.toughness)
+            }
+        }
+    }
+}
+
+impl Battlefield {
+    pub fn add_continuous_effect(
+        &mut self,
+        affected: Affected,
+        modification: Modification,
+        duration: Duration,
+    ) {
+        self.continuous_effects.push(ContinuousEffect {
+            timestamp: Timestamp::next(),
+            affected,
+            modification,
+            duration,
+        });
+        self.apply_continuous_effects();
+    }
+
+    // "Until end of turn" effects end during the cleanup step.
+    pub(crate) fn end_turn_effects(&mut self) {
+        self.continuous_effects
+            .retain(|e| e.duration != Duration::EndOfTurn);
+        self.apply_continuous_effects();
+    }
+
+    // Recomputes the characteristics (and controller) of every permanent from its card
+    // and the continuous effects, applied by layer and then by timestamp.
+    // This needs to happen whenever an effect begins or ends, and whenever
+    // a permanent enters or leaves the battlefield.
+    // TODO: dependencies between effects in the same layer.
+    pub fn apply_continuous_effects(&mut self) {
+        // Effects on particular objects end once none of them are on the battlefield.
+        let objects = &self.objects;
+        self.continuous_effects.retain(|e| match &e.affected {
+            Affected::Objects(ids) => ids.iter().any(|id| objects.iter().any(|o| o.id() == *id)),
+            Affected::Permanents { .. } => true,
+        });
+
+        let mut effects: Vec<(Timestamp, Affected, Modification)> = self
+            .continuous_effects
+            .iter()
+            .map(|e| (e.timestamp, e.affected.clone(), e.modification.clone()))
+            .collect();
+        // Counters apply along with the other effects in their layers.
+        for object in &self.objects {
+            let plus = object.count_counters(&CounterKind::PlusOnePlusOne) as i32;
+            let minus = object.count_counters(&CounterKind::MinusOneMinusOne) as i32;
+            if plus != minus {
+                let modification = Modification::ModifyPowerToughness {
+                    power: plus - minus,
+                    toughness: plus - minus,
+                };
+                let affected = Affected::Objects(vec![object.id()]);
+                effects.push((object.timestamp, affected, modification));
+            }
+            for counters in &object.counters {
+                if let CounterKind::Keyword(keyword) = counters.kind {
+                    let modification = Modification::AddAbility(Box::new(keyword.ability()));
+                    let affected = Affected::Objects(vec![object.id()]);
+                    effects.push((object.timestamp, affected, modification));
+                }
+            }
+        }
+
+        let previous_controllers: Vec<PlayerId> =
+            self.objects.iter().map(|o| o.controller).collect();
+        for object in self.objects.iter_mut() {
+            object.characteristics = object.printed_characteristics();
+            object.controller = object.default_controller;
+        }
+        for layer in Layer::ALL {
+            // Static abilities come from the characteristics (and controller) computed
+            // in the earlier layers, so e.g. an ability removed in the ability layer
+            // no longer changes power and toughness.
+            let mut statics = Vec::new();
+            for object in &self.objects {
+                for ability in object.abilities() {
+                    if let Ability::Static(ability) = ability
+                        && ability.modification.layer() == layer
+                    {
+                        let affected = ability.affected.relative_to(object);
+                        statics.push((object.timestamp, affected, ability.modification.clone()));
+                    }
+                }
+            }
+            let mut layer_effects: Vec<&(Timestamp, Affected, Modification)> = (statics.iter())
+                .chain(effects.iter().filter(|(_, _, m)| m.layer() == layer))
+                .collect();
+            layer_effects.sort_by_key(|(timestamp, _, _)| *timestamp);
+            for (_, affected, modification) in layer_effects {
+                let affected: Vec<bool> =
+                    self.objects.iter().map(|o| affected.contains(o)).collect();
+                for (object, _) in self.objects.iter_mut().zip(affected).filter(|(_, a)| *a) {
+                    match modification {
+                        Modification::Control(player) => object.controller = *player,
+                        _ => object.characteristics.apply(modification),
+                    }
+                }
+            }
+        }
+        // A permanent which changed control has not been continuously controlled
+        // since the start of its new controller's turn.
+        for (object, previous) in self.objects.iter_mut().zip(previous_controllers) {
+            if object.controller != previous {
+                object.summoning_sick = true;
+            }
+        }
+    }
+}
+
+#[test]
+fn test_continuous_effects() {
+    use crate::{
+        card::{
+            abilities::{AffectedPermanents, StaticAbility},
+            test_card,
+            types::{CreatureProperties, EnchantmentProperties, Power, Toughness},
+        },
+        game_play::{agent::Goldfish, player::PlayerState, turn::Step},
+    };
+
+    let bear = test_card(
+        "Bear",
+        Type::Creature(CreatureProperties {
+            subtypes: Vec::new(),
+            power: Power::Value(2),
+            toughness: Toughness::Value(2),
+        }),
+        Vec::new(),
+    );
+    let anthem = test_card(
+        "Anthem",
+        Type::Enchantment(EnchantmentProperties {
+            subtypes: Vec::new(),
+        }),
+        vec![Ability::Static(StaticAbility {
+            affected: AffectedPermanents::YouControl(PermanentFilter::Creature),
+            modification: Modification::ModifyPowerToughness {
+                power: 1,
+                toughness: 1,
+            },
+        })],
+    );
+    let mut battlefield = Battlefield::new(vec![
+        PlayerState::new(0, vec![bear, anthem]),
+        PlayerState::new(1, Vec::new()),
+    ]);
+    let (player, opponent) = (battlefield.players[0].id, battlefield.players[1].id);
+    while let Some(card) = battlefield.players[0].zones.library.pop_front() {
+        battlefield.objects.push(InPlayObject::new(card));
+    }
+    battlefield.apply_continuous_effects();
+    let find = |name: &str| {
+        let object = battlefield
+            .objects
+            .iter()
+            .find(|o| o.card.card.name() == name);
+        object.unwrap().id()
+    };
+    let (bear, anthem) = (find("Bear"), find("Anthem"));
+    let power_toughness = |battlefield: &Battlefield| {
+        let object = battlefield.object(bear).unwrap();
+        (object.power(), object.toughness())
+    };
+    assert_eq!(power_toughness(&battlefield), (3, 3));
+
+    // Setting power and toughness applies before the anthem, despite the later timestamp.
+    battlefield.add_continuous_effect(
+        Affected::Objects(vec![bear]),
+        Modification::SetPowerToughness {
+            power: 0,
+            toughness: 4,
+        },
+        Duration::EndOfTurn,
+    );
+    assert_eq!(power_toughness(&battlefield), (1, 5));
+    battlefield.add_continuous_effect(
+        Affected::Objects(vec![bear]),
+        Modification::SwitchPowerToughness,
+        Duration::EndOfTurn,
+    );
+    assert_eq!(power_toughness(&battlefield), (5, 1));
+
+    // Gaining control of the bear: it no longer gets the anthem bonus.
+    battlefield.object_mut(bear).unwrap().summoning_sick = false;
+    battlefield.add_continuous_effect(
+        Affected::Objects(vec![bear]),
+        Modification::Control(opponent),
+        Duration::EndOfTurn,
+    );
+    let object = battlefield.object(bear).unwrap();
+    assert_eq!(object.controller, opponent);
+    assert!(object.has_summoning_sickness());
+    assert_eq!(power_toughness(&battlefield), (4, 0));
+
+    // The effects end during cleanup.
+    battlefield.turn.step = Step::End;
+    battlefield.advance_step(&mut Goldfish);
+    assert_eq!(battlefield.object(bear).unwrap().controller, player);
+    assert_eq!(power_toughness(&battlefield), (3, 3));
+
+    // Gaining control of the anthem: it applies to the new controller's creatures.
+    battlefield.add_continuous_effect(
+        Affected::Objects(vec![anthem]),
+        Modification::Control(opponent),
+        Duration::EndOfTurn,
+    );
+    assert_eq!(power_toughness(&battlefield), (2, 2));
+    battlefield.turn.step = Step::End;
+    battlefield.advance_step(&mut Goldfish);
+    assert_eq!(power_toughness(&battlefield), (3, 3));
+
+    // An anthem which loses its abilities no longer applies.
+    battlefield.add_continuous_effect(
+        Affected::Objects(vec![anthem]),
+        Modification::LoseAllAbilities,
+        Duration::Indefinite,
+    );
+    assert_eq!(power_toughness(&battlefield), (2, 2));
+
+    // The anthem stops applying once it leaves the battlefield.
+    battlefield.put_into_graveyard(anthem);
+    assert_eq!(power_toughness(&battlefield), (2, 2));
+    assert!(battlefield.continuous_effects.is_empty());
+}
@@ -1,11 +1,66 @@
-use crate::{card::Card, game_play::player::PlayerId};
+use {
+    crate::{card::Card, game_play::player::PlayerId},
+    std::sync::atomic::{AtomicU64, Ordering},
+};
 
+pub mod agent;
 pub mod battlefield;
+pub mod combat;
 pub mod counters;
+pub mod layers;
 pub mod player;
+pub mod stack;
+pub mod state_based_actions;
+pub mod target;
+pub mod turn;
+
+/// Identifies an object (a card in some zone, a permanent or a spell).
+/// A card which changes zones becomes a new object with a new id, so e.g. a creature
+/// which dies and returns to the battlefield is not the same object as before.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ObjectId(u64);
+
+impl ObjectId {
+    // Ids are unique across all games, so objects from different games
+    // (e.g. simulations running in parallel) are never confused.
+    pub fn next() -> Self {
+        static NEXT: AtomicU64 = AtomicU64::new(0);
+        Self(NEXT.fetch_add(1, Ordering::Relaxed))
+    }
+}
 
 #[derive(Debug, Clone)]
 pub struct OwnedCard {
+    pub id: ObjectId,
     pub card: Card,
     pub owner: PlayerId,
+    // Tokens cease to exist once they leave the battlefield
+    // (see `check_state_based_actions`).
+    pub is_token: bool,
+}
+
+impl OwnedCard {
+    pub fn new(card: Card, owner: PlayerId) -> Self {
+        Self {
+            id: ObjectId::next(),
+            card,
+            owner,
+            is_token: false,
+        }
+    }
+
+    pub fn token(card: Card, owner: PlayerId) -> Self {
+        Self {
+            is_token: true,
+            ..Self::new(card, owner)
+        }
+    }
+
+    // The card moving to another zone becomes a new object.
+    pub fn change_zone(self) -> Self {
+        Self {
+            id: ObjectId::next(),
+            ..self
+        }
+    }
 }
@@ -2,7 +2,14 @@ use std::collections::VecDeque;
 
 use rand::seq::SliceRandom;
 
-use crate::{card::Card, game_play::OwnedCard};
+use crate::{
+    card::Card,
+    game_play::{
+        ObjectId, OwnedCard,
+        counters::{self, CounterKind, Counters},
+    },
+    mana::pool::ManaPool,
+};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct PlayerId(u32);
@@ -11,7 +18,14 @@ pub struct PlayerId(u32);
 pub struct PlayerState {
     pub id: PlayerId,
     pub life_total: i32,
+    // Poison, energy, experience, etc.
+    pub counters: Vec<Counters>,
+    // Set when the player attempts to draw from an empty library;
+    // they lose the game when state-based actions are next checked.
+    pub drew_from_empty_library: bool,
+    pub has_lost: bool,
     pub zones: Zones,
+    pub mana_pool: ManaPool,
 }
 
 impl PlayerState {
@@ -21,27 +35,71 @@ impl PlayerState {
         let id = PlayerId(id);
         let mut library: Vec<OwnedCard> = deck
             .into_iter()
-            .map(|card| OwnedCard { card, owner: id })
+            .map(|card| OwnedCard::new(card, id))
             .collect();
         library.shuffle(&mut rand::rng());
         Self {
             id,
             life_total: 20,
+            counters: Vec::new(),
+            drew_from_empty_library: false,
+            has_lost: false,
             zones: Zones {
                 hand: Vec::new(),
                 library: library.into(),
                 graveyard: Vec::new(),
                 exile: Vec::new(),
             },
+            mana_pool: ManaPool::default(),
         }
     }
 
-    // Returns None if the draw failed
+    pub fn count_counters(&self, kind: &CounterKind) -> u32 {
+        counters::count(&self.counters, kind)
+    }
+
+    pub fn shuffle_hand_into_library(&mut self) {
+        let mut library: Vec<OwnedCard> = self
+            .zones
+            .hand
+            .drain(..)
+            .map(OwnedCard::change_zone)
+            .collect();
+        library.extend(self.zones.library.drain(..));
+        library.shuffle(&mut rand::rng());
+        self.zones.library = library.into();
+    }
+
+    // Returns `Decked` if the library is empty.
     pub fn draw_a_card(&mut self) -> Result<(), Decked> {
-        let card = self.zones.library.pop_front().ok_or(Decked)?;
-        self.zones.hand.push(card);
+        let Some(card) = self.zones.library.pop_front() else {
+            self.drew_from_empty_library = true;
+            return Err(Decked);
+        };
+        self.zones.hand.push(card.change_zone());
         Ok(())
     }
+
+    pub fn take_from_hand(&mut self, card: ObjectId) -> Option<OwnedCard> {
+        let index = self.zones.hand.iter().position(|c| c.id == card)?;
+        Some(self.zones.hand.remove(index))
+    }
+
+    // The card becomes a new object in the library, which is then shuffled.
+    pub fn shuffle_into_library(&mut self, card: OwnedCard) {
+        let mut library: Vec<OwnedCard> = self.zones.library.drain(..).collect();
+        library.push(card.change_zone());
+        library.shuffle(&mut rand::rng());
+        self.zones.library = library.into();
+    }
+
+    // The card becomes a new object in exile.
+    pub fn exile(&mut self, card: OwnedCard, on_adventure: bool) {
+        self.zones.exile.push(ExiledCard {
+            card: card.change_zone(),
+            on_adventure,
+        });
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -49,7 +107,40 @@ pub struct Zones {
     pub hand: Vec<OwnedCard>,
     pub library: VecDeque<OwnedCard>,
     pub graveyard: Vec<OwnedCard>,
-    pub exile: Vec<OwnedCard>,
+    pub exile: Vec<ExiledCard>,
+}
+
+impl Zones {
+    // A card which can be cast from the hand, or from exile while it is on an adventure.
+    // The flag is set for the latter, when only the primary face can be cast.
+    pub fn castable(&self, card: ObjectId) -> Option<(&OwnedCard, bool)> {
+        let in_hand = self.hand.iter().find(|c| c.id == card);
+        in_hand.map(|c| (c, false)).or_else(|| {
+            self.exile
+                .iter()
+                .find(|c| c.on_adventure && c.card.id == card)
+                .map(|c| (&c.card, true))
+        })
+    }
+
+    pub fn take_castable(&mut self, card: ObjectId) -> Option<OwnedCard> {
+        if let Some(index) = self.hand.iter().position(|c| c.id == card) {
+            return Some(self.hand.remove(index));
+        }
+        let index = self
+            .exile
+            .iter()
+            .position(|c| c.on_adventure && c.card.id == card)?;
+        Some(self.exile.remove(index).card)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ExiledCard {
+    pub card: OwnedCard,
+    // An adventurer card exiled as its adventure resolved, which its owner may cast
+    // as the primary face (e.g. the creature) later.
+    pub on_adventure: bool,
 }
 
 #[derive(Debug, Clone, Copy)]
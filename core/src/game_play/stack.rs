@@ -0,0 +1,98 @@
+use crate::{
+    card::{CastFace, abilities::Effect},
+    game_play::{
+        OwnedCard, battlefield::InPlayObject, layers::Characteristics, player::PlayerId,
+        target::Target,
+    },
+};
+
+/// Spells and abilities waiting to resolve. The last object is the top of the stack.
+#[derive(Debug, Clone, Default)]
+pub struct Stack {
+    pub objects: Vec<StackObject>,
+}
+
+impl Stack {
+    pub fn push(&mut self, object: StackObject) {
+        self.objects.push(object);
+    }
+
+    pub fn pop(&mut self) -> Option<StackObject> {
+        self.objects.pop()
+    }
+
+    pub fn top(&self) -> Option<&StackObject> {
+        self.objects.last()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.objects.is_empty()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct StackObject {
+    pub controller: PlayerId,
+    pub kind: StackObjectKind,
+    // Chosen as the object is put on the stack.
+    pub targets: Vec<Target>,
+    // The permanent an ability came from, as it last existed on the battlefield.
+    // `None` for spells.
+    pub source: Option<InPlayObject>,
+}
+
+impl StackObject {
+    // The characteristics of a spell are those of the face(s) being cast.
+    // Abilities have none.
+    pub fn characteristics(&self) -> Option<Characteristics> {
+        match &self.kind {
+            StackObjectKind::Spell { card, face, .. } => {
+                Some(Characteristics::printed(&card.card.cast_faces(*face)))
+            }
+            StackObjectKind::Ability(_) => None,
+        }
+    }
+
+    pub fn mana_value(&self) -> u8 {
+        match &self.kind {
+            StackObjectKind::Spell { card, face, x } => card.card.cast_mana_value(*face, *x),
+            StackObjectKind::Ability(_) => 0,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum StackObjectKind {
+    // E.g. the door of a Room being cast.
+    Spell {
+        card: Box<OwnedCard>,
+        face: CastFace,
+        // The value chosen for X as the spell was cast.
+        x: u8,
+    },
+    Ability(Effect),
+}
+
+/// Tracks which player may currently act and how many players in a row
+/// have passed without doing anything.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Priority {
+    pub holder: PlayerId,
+    pub passes: usize,
+}
+
+impl Priority {
+    pub fn new(holder: PlayerId) -> Self {
+        Self { holder, passes: 0 }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PriorityOutcome {
+    // Priority moved to the next player.
+    Passed,
+    // All players passed in succession, so the top of the stack resolved.
+    Resolved,
+    // All players passed in succession with an empty stack.
+    StackEmpty,
+}
@@ -0,0 +1,208 @@
+use crate::game_play::{
+    ObjectId,
+    agent::Agent,
+    battlefield::{Battlefield, Event, InPlayObject},
+    counters::CounterKind,
+    player::PlayerId,
+    target::Target,
+};
+
+impl Battlefield {
+    // Performs state-based actions, repeating until there are none left to perform.
+    // Each action is logged as an `Event`.
+    pub fn check_state_based_actions(&mut self, agent: &mut dyn Agent) {
+        while self.perform_state_based_actions(agent) {}
+    }
+
+    // All applicable actions are performed at once. Returns whether any were.
+    fn perform_state_based_actions(&mut self, agent: &mut dyn Agent) -> bool {
+        let losers: Vec<PlayerId> = self
+            .players
+            .iter()
+            .filter(|p| {
+                !p.has_lost
+                    && (p.life_total <= 0
+                        || p.drew_from_empty_library
+                        || p.count_counters(&CounterKind::Poison) >= 10)
+            })
+            .map(|p| p.id)
+            .collect();
+
+        // A +1/+1 and a -1/-1 counter are removed in pairs.
+        let cancelled: Vec<(ObjectId, u32)> = self
+            .objects
+            .iter()
+            .map(|o| {
+                let plus = o.count_counters(&CounterKind::PlusOnePlusOne);
+                (
+                    o.id(),
+                    plus.min(o.count_counters(&CounterKind::MinusOneMinusOne)),
+                )
+            })
+            .filter(|(_, amount)| *amount > 0)
+            .collect();
+
+        // Tokens which left the battlefield cease to exist.
+        let tokens_gone = self.players.iter().any(|p| {
+            let zones = &p.zones;
+            (zones.hand.iter())
+                .chain(&zones.library)
+                .chain(&zones.graveyard)
+                .chain(zones.exile.iter().map(|c| &c.card))
+                .any(|c| c.is_token)
+        });
+
+        let mut to_graveyard = Vec::new();
+        let mut destroyed = Vec::new();
+        for object in &self.objects {
+            let has_no_loyalty =
+                object.is_planeswalker() && object.count_counters(&CounterKind::Loyalty) == 0;
+            let has_no_defense =
+                object.is_battle() && object.count_counters(&CounterKind::Defense) == 0;
+            // A Siege must be protected by an opponent of its controller.
+            let has_no_protector =
+                object.is_siege() && object.protector.is_none_or(|p| p == object.controller);
+            if object.has_zero_toughness() || has_no_loyalty || has_no_defense || has_no_protector {
+                to_graveyard.push(object.id());
+            } else if object.is_creature()
+                && object.characteristics.toughness.is_some()
+                && object.damage > 0
+                && object.lethal_damage() == 0
+            {
+                destroyed.push(object.id());
+            }
+        }
+
+        // Legend rule: groups of legendary permanents with the same name and controller.
+        let mut legends: Vec<Vec<&InPlayObject>> = Vec::new();
+        for object in self.objects.iter().filter(|o| o.is_legendary()) {
+            let same = legends.iter_mut().find(|group| {
+                group[0].controller == object.controller
+                    && group[0].characteristics.name == object.characteristics.name
+            });
+            match same {
+                Some(group) => group.push(object),
+                None => legends.push(vec![object]),
+            }
+        }
+        let legends: Vec<(PlayerId, Vec<ObjectId>)> = legends
+            .into_iter()
+            .filter(|group| group.len() > 1)
+            .map(|group| (group[0].controller, group.iter().map(|o| o.id()).collect()))
+            .collect();
+        for (controller, group) in legends {
+            let keep = agent.choose_legend_to_keep(self, controller, &group);
+            to_graveyard.extend(group.into_iter().filter(|id| *id != keep));
+        }
+
+        for player in &losers {
+            self.player_mut(*player).has_lost = true;
+            self.log_event(Event::LostGame(*player));
+        }
+        for (id, amount) in &cancelled {
+            let target = Target::Permanent(*id);
+            self.remove_counters(target, CounterKind::PlusOnePlusOne, *amount);
+            self.remove_counters(target, CounterKind::MinusOneMinusOne, *amount);
+        }
+        if tokens_gone {
+            for player in self.players.iter_mut() {
+                let zones = &mut player.zones;
+                zones.hand.retain(|c| !c.is_token);
+                zones.library.retain(|c| !c.is_token);
+                zones.graveyard.retain(|c| !c.is_token);
+                zones.exile.retain(|c| !c.card.is_token);
+            }
+        }
+        let removed = tokens_gone || !to_graveyard.is_empty() || !destroyed.is_empty();
+        for id in destroyed {
+            if !to_graveyard.contains(&id) {
+                self.destroy(id);
+            }
+        }
+        for id in to_graveyard {
+            self.put_into_graveyard(id);
+        }
+        // TODO: a player who lost should leave the game (with the objects they own).
+        !losers.is_empty() || removed || !cancelled.is_empty()
+    }
+}
+
+#[test]
+fn test_state_based_actions() {
+    use crate::{
+        card::{
+            Card, CardFace, test_card, test_face,
+            types::{CreatureProperties, Power, Toughness, Type},
+        },
+        game_play::{agent::Goldfish, player::PlayerState},
+    };
+
+    let creature = |name: &str, is_legendary: bool, toughness: u32| {
+        let creature = Type::Creature(CreatureProperties {
+            subtypes: Vec::new(),
+            power: Power::Value(1),
+            toughness: Toughness::Value(toughness),
+        });
+        Card::Single(CardFace {
+            is_legendary,
+            ..test_face(name, creature, Vec::new())
+        })
+    };
+    let deck = vec![
+        creature("Legend", true, 1),
+        creature("Legend", true, 1),
+        creature("Wall", false, 0),
+        creature("Bear", false, 2),
+        // Dynamic toughness (*/*) is not computed yet, so it is not lethal damage.
+        test_card(
+            "Star",
+            Type::Creature(CreatureProperties {
+                subtypes: Vec::new(),
+                power: Power::Dynamic { modifier: None },
+                toughness: Toughness::Dynamic { modifier: None },
+            }),
+            Vec::new(),
+        ),
+    ];
+    let mut battlefield = Battlefield::new(vec![
+        PlayerState::new(0, deck),
+        PlayerState::new(1, Vec::new()),
+    ]);
+    while let Some(card) = battlefield.players[0].zones.library.pop_front() {
+        battlefield.objects.push(InPlayObject::new(card));
+    }
+    let bear = battlefield
+        .objects
+        .iter_mut()
+        .find(|o| o.card.card.name() == "Bear")
+        .unwrap();
+    bear.damage = 2;
+    let star = battlefield
+        .objects
+        .iter_mut()
+        .find(|o| o.card.card.name() == "Star")
+        .unwrap();
+    star.damage = 1;
+    battlefield.add_counters(
+        Target::Player(battlefield.players[1].id),
+        CounterKind::Poison,
+        10,
+    );
+    battlefield.players[0].draw_a_card().unwrap_err();
+
+    battlefield.check_state_based_actions(&mut Goldfish);
+    let names: Vec<String> = (battlefield.objects.iter())
+        .map(|o| o.card.card.name())
+        .collect();
+    assert_eq!(names.len(), 2);
+    assert!(names.contains(&"Legend".to_string()));
+    assert!(names.contains(&"Star".to_string()));
+    assert_eq!(battlefield.players[0].zones.graveyard.len(), 3);
+    assert!(battlefield.players.iter().all(|p| p.has_lost));
+    let lost = |e: &&Event| matches!(e, Event::LostGame(_));
+    assert_eq!(battlefield.log.iter().filter(lost).count(), 2);
+
+    // Nothing is repeated once the actions are done.
+    battlefield.check_state_based_actions(&mut Goldfish);
+    assert_eq!(battlefield.log.iter().filter(lost).count(), 2);
+}
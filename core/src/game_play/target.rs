@@ -0,0 +1,134 @@
+use crate::{
+    card::abilities::TargetSpec,
+    game_play::{
+        ObjectId, OwnedCard,
+        battlefield::{Battlefield, InPlayObject},
+        player::PlayerId,
+    },
+};
+
+/// Something chosen as the target of a spell or ability.
+/// A permanent or card which changes zones becomes a new object, so it is no longer a legal target.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Target {
+    Player(PlayerId),
+    Permanent(ObjectId),
+    CardInGraveyard(ObjectId),
+}
+
+// A card which could not be cast because one of its targets has no legal choice.
+#[derive(Debug, Clone)]
+pub struct NoLegalTargets(pub Box<OwnedCard>);
+
+/// Information about the spell or ability that is resolving.
+#[derive(Debug, Clone)]
+pub struct Resolution {
+    pub controller: PlayerId,
+    // The permanent the ability came from (see `StackObject::source`).
+    pub source: Option<InPlayObject>,
+    // The value of X chosen as the spell was cast (0 for abilities).
+    pub x: u8,
+    // One entry per target; `None` if that target was no longer legal on resolution.
+    pub targets: Vec<Option<Target>>,
+}
+
+impl Resolution {
+    pub fn target_player(&self, index: usize) -> Option<PlayerId> {
+        match self.targets.get(index) {
+            Some(Some(Target::Player(player))) => Some(*player),
+            _ => None,
+        }
+    }
+
+    pub fn target_permanent(&self, index: usize) -> Option<ObjectId> {
+        match self.targets.get(index) {
+            Some(Some(Target::Permanent(id))) => Some(*id),
+            _ => None,
+        }
+    }
+}
+
+impl Battlefield {
+    pub fn legal_targets(&self, controller: PlayerId, spec: TargetSpec) -> Vec<Target> {
+        match spec {
+            TargetSpec::Player | TargetSpec::Opponent => self
+                .players
+                .iter()
+                .map(|p| Target::Player(p.id))
+                .filter(|t| self.is_legal_target(controller, spec, *t))
+                .collect(),
+            TargetSpec::Creature | TargetSpec::CreatureYouControl | TargetSpec::Permanent => self
+                .objects
+                .iter()
+                .map(|o| Target::Permanent(o.id()))
+                .filter(|t| self.is_legal_target(controller, spec, *t))
+                .collect(),
+            TargetSpec::CardInGraveyard => self
+                .players
+                .iter()
+                .flat_map(|p| &p.zones.graveyard)
+                .map(|c| Target::CardInGraveyard(c.id))
+                .collect(),
+        }
+    }
+
+    pub fn is_legal_target(&self, controller: PlayerId, spec: TargetSpec, target: Target) -> bool {
+        match (spec, target) {
+            (TargetSpec::Player, Target::Player(player)) => {
+                self.players.iter().any(|p| p.id == player)
+            }
+            (TargetSpec::Opponent, Target::Player(player)) => {
+                player != controller && self.players.iter().any(|p| p.id == player)
+            }
+            (TargetSpec::Permanent, Target::Permanent(id)) => self.object(id).is_some(),
+            (TargetSpec::Creature, Target::Permanent(id)) => {
+                self.object(id).is_some_and(|o| o.is_creature())
+            }
+            (TargetSpec::CreatureYouControl, Target::Permanent(id)) => self
+                .object(id)
+                .is_some_and(|o| o.is_creature() && o.controller == controller),
+            (TargetSpec::CardInGraveyard, Target::CardInGraveyard(id)) => self
+                .players
+                .iter()
+                .any(|p| p.zones.graveyard.iter().any(|c| c.id == id)),
+            _ => false,
+        }
+    }
+}
+
+#[test]
+fn test_legal_targets() {
+    use crate::game_play::player::PlayerState;
+
+    let battlefield = Battlefield::new(vec![
+        PlayerState::new(0, Vec::new()),
+        PlayerState::new(1, Vec::new()),
+    ]);
+    let you = battlefield.players[0].id;
+    let opponent = battlefield.players[1].id;
+
+    assert_eq!(
+        battlefield.legal_targets(you, TargetSpec::Player),
+        vec![Target::Player(you), Target::Player(opponent)]
+    );
+    assert_eq!(
+        battlefield.legal_targets(you, TargetSpec::Opponent),
+        vec![Target::Player(opponent)]
+    );
+    // There is nothing on the battlefield or in graveyards to target
+    assert!(
+        battlefield
+            .legal_targets(you, TargetSpec::Creature)
+            .is_empty()
+    );
+    assert!(
+        battlefield
+            .legal_targets(you, TargetSpec::CardInGraveyard)
+            .is_empty()
+    );
+    assert!(!battlefield.is_legal_target(
+        you,
+        TargetSpec::Permanent,
+        Target::Permanent(ObjectId::next())
+    ));
+}
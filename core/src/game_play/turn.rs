@@ -0,0 +1,106 @@
+use crate::game_play::{ObjectId, player::PlayerId};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Phase {
+    Beginning,
+    PrecombatMain,
+    Combat,
+    PostcombatMain,
+    Ending,
+}
+
+/// Steps of a turn, in the order they happen.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Step {
+    Untap,
+    Upkeep,
+    Draw,
+    PrecombatMain,
+    BeginningOfCombat,
+    DeclareAttackers,
+    DeclareBlockers,
+    // Only happens if a creature in combat has first strike or double strike.
+    FirstStrikeDamage,
+    CombatDamage,
+    EndOfCombat,
+    PostcombatMain,
+    End,
+    Cleanup,
+}
+
+impl Step {
+    pub const fn phase(self) -> Phase {
+        match self {
+            Self::Untap | Self::Upkeep | Self::Draw => Phase::Beginning,
+            Self::PrecombatMain => Phase::PrecombatMain,
+            Self::BeginningOfCombat
+            | Self::DeclareAttackers
+            | Self::DeclareBlockers
+            | Self::FirstStrikeDamage
+            | Self::CombatDamage
+            | Self::EndOfCombat => Phase::Combat,
+            Self::PostcombatMain => Phase::PostcombatMain,
+            Self::End | Self::Cleanup => Phase::Ending,
+        }
+    }
+
+    // Returns `None` after the cleanup step since the turn is over.
+    pub const fn next(self) -> Option<Self> {
+        match self {
+            Self::Untap => Some(Self::Upkeep),
+            Self::Upkeep => Some(Self::Draw),
+            Self::Draw => Some(Self::PrecombatMain),
+            Self::PrecombatMain => Some(Self::BeginningOfCombat),
+            Self::BeginningOfCombat => Some(Self::DeclareAttackers),
+            Self::DeclareAttackers => Some(Self::DeclareBlockers),
+            Self::DeclareBlockers => Some(Self::FirstStrikeDamage),
+            Self::FirstStrikeDamage => Some(Self::CombatDamage),
+            Self::CombatDamage => Some(Self::EndOfCombat),
+            Self::EndOfCombat => Some(Self::PostcombatMain),
+            Self::PostcombatMain => Some(Self::End),
+            Self::End => Some(Self::Cleanup),
+            Self::Cleanup => None,
+        }
+    }
+
+    pub const fn is_main_phase(self) -> bool {
+        matches!(self, Self::PrecombatMain | Self::PostcombatMain)
+    }
+
+    // Players do not normally receive priority during untap and cleanup.
+    pub const fn has_priority(self) -> bool {
+        !matches!(self, Self::Untap | Self::Cleanup)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Turn {
+    // Number of turns taken so far in the game (by any player)
+    pub number: u32,
+    pub active_player: PlayerId,
+    pub step: Step,
+    pub lands_played: u8,
+    // Permanents whose loyalty abilities were activated this turn.
+    pub loyalty_activated: Vec<ObjectId>,
+}
+
+impl Turn {
+    pub fn phase(&self) -> Phase {
+        self.step.phase()
+    }
+}
+
+#[test]
+fn test_step_order() {
+    let mut steps = vec![Step::Untap];
+    while let Some(step) = steps.last().unwrap().next() {
+        steps.push(step);
+    }
+    assert_eq!(steps.len(), 13);
+    assert_eq!(
+        steps.iter().filter(|s| s.phase() == Phase::Combat).count(),
+        6
+    );
+    assert_eq!(steps.iter().filter(|s| s.is_main_phase()).count(), 2);
+    assert_eq!(steps.last(), Some(&Step::Cleanup));
+}
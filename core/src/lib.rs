@@ -1,2 +1,3 @@
 pub mod card;
 pub mod game_play;
+pub mod mana;
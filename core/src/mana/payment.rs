@@ -0,0 +1,760 @@
+use crate::{
+    card::{
+        Card, CastFace,
+        abilities::ManaAbility,
+        mana_cost::{ManaCostRef, Payment, Pip, Unit},
+    },
+    game_play::{ObjectId, battlefield::Battlefield, player::PlayerId},
+    mana::pool::Mana,
+};
+
+#[derive(Debug, Clone, Default)]
+pub struct PaymentOptions<'a> {
+    // The spell (and the face being cast) being paid for, if any,
+    // for mana with spending restrictions.
+    pub spell: Option<(&'a Card, CastFace)>,
+    // The chosen value of X
+    pub x: u8,
+    // Permanents which must stay untapped (e.g. to hold up mana for another spell).
+    pub excluded: Vec<ObjectId>,
+    // Most life to pay for Phyrexian mana.
+    pub life_budget: u32,
+    // Search every payment for one that taps the fewest permanents,
+    // instead of returning the first one found.
+    pub minimize_tapped: bool,
+}
+
+/// A way to pay a mana cost. Nothing is changed on the battlefield until it is carried out.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PaymentPlan {
+    // In the order the abilities should be activated.
+    pub activations: Vec<Activation>,
+    pub life: u32,
+    // Mana produced (or already in the pool) but not needed for the cost.
+    pub leftover: Vec<Mana>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Activation {
+    pub source: ObjectId,
+    // Index of the ability among the permanent's mana abilities
+    pub ability: usize,
+    pub produce: Vec<Unit>,
+}
+
+impl PaymentPlan {
+    // The permanents to tap
+    pub fn tapped(&self) -> Vec<ObjectId> {
+        self.activations.iter().map(|a| a.source).collect()
+    }
+}
+
+// Searches the mana pool and the mana abilities of the untapped permanents controlled
+// by `player` for a way to pay `cost`. Colored requirements (including hybrid and Phyrexian choices)
+// are searched with backtracking; generic mana is then paid by the sources left over.
+pub fn find_payment(
+    battlefield: &Battlefield,
+    player: PlayerId,
+    cost: ManaCostRef,
+    options: &PaymentOptions,
+) -> Option<PaymentPlan> {
+    let mut sources: Vec<Source> = battlefield
+        .objects
+        .iter()
+        .filter(|o| {
+            o.controller == player
+                && !o.tapped
+                && !o.has_summoning_sickness()
+                && !options.excluded.contains(&o.id())
+        })
+        .filter_map(|o| {
+            // TODO: mana abilities without a tap cost can be activated more than once.
+            let mut abilities: Vec<SourceAbility> = o
+                .mana_abilities()
+                .enumerate()
+                .filter(|(_, a)| {
+                    a.cost.tap
+                        && a.can_activate(battlefield, player)
+                        && a.restriction.is_none_or(|r| r.allows(options.spell))
+                })
+                .map(|(index, ability)| SourceAbility {
+                    index,
+                    ability,
+                    outputs: ability.produce.outputs(battlefield, player),
+                })
+                .collect();
+            abilities.sort_by_key(|a| a.ability.cost.mana_cost.as_ref().map(|c| c.mana_value()));
+            let mut units: Vec<&Unit> = Vec::new();
+            for unit in abilities.iter().flat_map(|a| a.ability.produce.units()) {
+                if !units.contains(&unit) {
+                    units.push(unit);
+                }
+            }
+            (!abilities.is_empty()).then(|| Source {
+                id: o.id(),
+                is_snow: o.is_snow(),
+                flexibility: units.len(),
+                abilities,
+            })
+        })
+        .collect();
+    // Try the sources which can produce the fewest kinds of mana first,
+    // to keep the flexible ones available.
+    sources.sort_by_key(|s| s.flexibility);
+
+    let life_total = u32::try_from(battlefield.player(player).life_total).unwrap_or(0);
+    let mut search = Search {
+        sources,
+        life_budget: options.life_budget.min(life_total),
+        minimize_tapped: options.minimize_tapped,
+        best: None,
+    };
+    let pool = battlefield.player(player).mana_pool.mana.iter();
+    let mut state = State {
+        used: vec![false; search.sources.len()],
+        requirements: Vec::new(),
+        generic: 0,
+        unpaid: Vec::new(),
+        plan: PaymentPlan {
+            leftover: pool
+                .filter(|m| m.restriction.is_none_or(|r| r.allows(options.spell)))
+                .cloned()
+                .collect(),
+            ..Default::default()
+        },
+    };
+    state.generic = state.add_requirements(cost.pips, options.x);
+    search.search(state);
+    search.best
+}
+
+struct Source<'a> {
+    id: ObjectId,
+    is_snow: bool,
+    flexibility: usize,
+    abilities: Vec<SourceAbility<'a>>,
+}
+
+impl Source<'_> {
+    // Sources which produce the same mana for the same costs are interchangeable,
+    // whichever permanents they are.
+    fn same_mana(&self, other: &Source) -> bool {
+        self.is_snow == other.is_snow
+            && self.abilities.len() == other.abilities.len()
+            && (self.abilities.iter().zip(&other.abilities)).all(|(a, b)| {
+                a.outputs == b.outputs
+                    && a.ability.cost == b.ability.cost
+                    && a.ability.restriction == b.ability.restriction
+            })
+    }
+}
+
+struct SourceAbility<'a> {
+    // Index among the permanent's mana abilities
+    index: usize,
+    ability: &'a ManaAbility,
+    outputs: Vec<Vec<Unit>>,
+}
+
+struct Search<'a> {
+    sources: Vec<Source<'a>>,
+    life_budget: u32,
+    minimize_tapped: bool,
+    best: Option<PaymentPlan>,
+}
+
+#[derive(Clone)]
+struct State {
+    // Indexed the same as `Search::sources`
+    used: Vec<bool>,
+    // Requirements which are not just generic mana, each with its payment options.
+    // The last one is paid first.
+    requirements: Vec<Vec<Payment>>,
+    generic: u32,
+    // Activations of abilities with a mana cost which is not paid yet, the last one first.
+    unpaid: Vec<Unpaid>,
+    plan: PaymentPlan,
+}
+
+#[derive(Clone)]
+struct Unpaid {
+    // The cost is paid once `State::requirements` is back to this length,
+    // and then the generic part is paid.
+    requirements: usize,
+    generic: u32,
+    activation: Activation,
+    // The mana produced which is not used yet. It cannot be spent
+    // before the cost is paid, in particular not on the ability's own cost.
+    rest: Vec<Mana>,
+}
+
+impl State {
+    // Returns the generic mana in the cost.
+    fn add_requirements(&mut self, pips: &[Pip], x: u8) -> u32 {
+        let mut generic = 0;
+        for pip in pips {
+            match &pip.payment_options(x)[..] {
+                [Payment::Generic { amount }] => generic += u32::from(*amount),
+                options => self.requirements.push(options.to_vec()),
+            }
+        }
+        generic
+    }
+
+    // `rest` is the part of `produce` which is not spent right away.
+    fn activate(
+        &self,
+        source: &Source,
+        ability: usize,
+        produce: Vec<Unit>,
+        rest: Vec<Unit>,
+        s: usize,
+    ) -> Self {
+        let mut next = self.clone();
+        next.used[s] = true;
+        let SourceAbility {
+            index,
+            ability: mana_ability,
+            ..
+        } = source.abilities[ability];
+        let activation = Activation {
+            source: source.id,
+            ability: index,
+            produce,
+        };
+        let rest = rest
+            .into_iter()
+            .map(|unit| Mana {
+                unit,
+                restriction: mana_ability.restriction,
+                snow: source.is_snow,
+            })
+            .collect();
+        if let Some(cost) = &mana_ability.cost.mana_cost {
+            let requirements = next.requirements.len();
+            let generic = next.add_requirements(&cost.pips, 0);
+            next.unpaid.push(Unpaid {
+                requirements,
+                generic,
+                activation,
+                rest,
+            });
+        } else {
+            // Abilities without a mana cost go first, so their mana can pay for the others.
+            next.plan.activations.insert(0, activation);
+            next.plan.leftover.extend(rest);
+        }
+        next
+    }
+}
+
+impl Search<'_> {
+    fn is_done(&self) -> bool {
+        self.best.is_some() && !self.minimize_tapped
+    }
+
+    fn search(&mut self, mut state: State) {
+        if self.is_done() {
+            return;
+        }
+        // Once the cost of an activation is paid, it can be activated and its mana used.
+        while let Some(unpaid) = state.unpaid.last()
+            && unpaid.requirements == state.requirements.len()
+        {
+            let unpaid = state.unpaid.pop().expect("checked above");
+            if !self.pay_generic(&mut state, unpaid.generic) {
+                return;
+            }
+            state.plan.activations.push(unpaid.activation);
+            state.plan.leftover.extend(unpaid.rest);
+        }
+        let Some(requirement) = state.requirements.pop() else {
+            self.finish(state);
+            return;
+        };
+        for option in requirement {
+            match option {
+                Payment::Life { amount } => {
+                    if state.plan.life + u32::from(amount) <= self.life_budget {
+                        let mut next = state.clone();
+                        next.plan.life += u32::from(amount);
+                        self.search(next);
+                    }
+                }
+                Payment::Generic { amount } => {
+                    let mut next = state.clone();
+                    next.generic += u32::from(amount);
+                    self.search(next);
+                }
+                Payment::Mana(unit) => {
+                    for next in self.branches(&state, &unit) {
+                        self.search(next);
+                    }
+                }
+            }
+        }
+    }
+
+    // The ways to pay for one mana of the given kind:
+    // from leftover mana or by activating one of the unused sources.
+    fn branches(&self, state: &State, unit: &Unit) -> Vec<State> {
+        let mut branches = Vec::new();
+        // E.g. both snow and non-snow green mana might pay for {G}.
+        let mut tried: Vec<&Mana> = Vec::new();
+        for (i, mana) in state.plan.leftover.iter().enumerate() {
+            if mana.pays(unit) && !tried.contains(&mana) {
+                tried.push(mana);
+                let mut next = state.clone();
+                next.plan.leftover.swap_remove(i);
+                branches.push(next);
+            }
+        }
+        let mut tried: Vec<&Source> = Vec::new();
+        for (s, source) in self.sources.iter().enumerate() {
+            if state.used[s] || tried.iter().any(|t| t.same_mana(source)) {
+                continue;
+            }
+            tried.push(source);
+            let pays = |u: &Unit| match unit {
+                Unit::Snow => source.is_snow,
+                unit => u == unit,
+            };
+            for (ability, source_ability) in source.abilities.iter().enumerate() {
+                for produce in &source_ability.outputs {
+                    let Some(i) = produce.iter().position(pays) else {
+                        continue;
+                    };
+                    let mut rest = produce.clone();
+                    rest.remove(i);
+                    branches.push(state.activate(source, ability, produce.clone(), rest, s));
+                }
+            }
+        }
+        branches
+    }
+
+    // Abilities with a mana cost are not used for generic mana since (producing one mana)
+    // they do not pay for more than they cost.
+    // Returns false if there is not enough mana.
+    fn pay_generic(&self, state: &mut State, mut generic: u32) -> bool {
+        let from_leftover = state.plan.leftover.len().min(generic as usize);
+        state.plan.leftover.drain(..from_leftover);
+        generic -= from_leftover as u32;
+
+        let mut candidates: Vec<(usize, usize, Vec<Unit>)> = self
+            .sources
+            .iter()
+            .enumerate()
+            .filter(|(s, _)| !state.used[*s])
+            .filter_map(|(s, source)| {
+                source
+                    .abilities
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, a)| a.ability.cost.mana_cost.is_none())
+                    .flat_map(|(ability, a)| {
+                        a.outputs
+                            .iter()
+                            .map(move |produce| (s, ability, produce.clone()))
+                    })
+                    .max_by_key(|(_, _, produce)| produce.len())
+            })
+            .collect();
+        // Prefer sources which produce the most mana, then the least flexible ones.
+        candidates.sort_by_key(|(s, _, produce)| {
+            (usize::MAX - produce.len(), self.sources[*s].flexibility)
+        });
+        for (s, ability, produce) in candidates {
+            if generic == 0 {
+                break;
+            }
+            let paid = produce.len().min(generic as usize);
+            let rest = produce[paid..].to_vec();
+            *state = state.activate(&self.sources[s], ability, produce, rest, s);
+            generic -= paid as u32;
+        }
+        generic == 0
+    }
+
+    fn finish(&mut self, mut state: State) {
+        let generic = state.generic;
+        if !self.pay_generic(&mut state, generic) {
+            return;
+        }
+        let is_better = self
+            .best
+            .as_ref()
+            .is_none_or(|best| state.plan.activations.len() < best.activations.len());
+        if is_better {
+            self.best = Some(state.plan);
+        }
+    }
+}
+
+#[test]
+fn test_find_payment() {
+    use crate::{
+        card::{
+            abilities::{Ability, ManaProduction},
+            test_card,
+            types::{LandProperties, Type},
+        },
+        game_play::{agent::Goldfish, player::PlayerState},
+    };
+
+    let land = |name: &str, produce: ManaProduction| {
+        test_card(
+            name,
+            Type::Land(LandProperties {
+                is_basic: true,
+                subtypes: Vec::new(),
+            }),
+            vec![Ability::Mana(ManaAbility::tap(produce))],
+        )
+    };
+    let deck = vec![
+        land("Swamp", ManaProduction::black()),
+        land("Forest", ManaProduction::green()),
+    ];
+    let mut battlefield = Battlefield::new(vec![PlayerState::new(0, deck)]);
+    let player = battlefield.players[0].id;
+    while let Some(card) = battlefield.players[0].zones.library.pop_front() {
+        battlefield.play_land(card, &mut Goldfish);
+    }
+    let pay = |cost: &str, options: &PaymentOptions| {
+        let cost: crate::card::mana_cost::ManaCost = cost.parse().unwrap();
+        find_payment(&battlefield, player, cost.as_ref(), options)
+    };
+
+    // The hybrid pip has to be paid with green, which is only found by backtracking.
+    let plan = pay("{B}{B/G}", &PaymentOptions::default()).unwrap();
+    assert_eq!(plan.tapped().len(), 2);
+    assert!(battlefield.objects.iter().all(|o| !o.tapped));
+    assert!(pay("{B}{B}", &PaymentOptions::default()).is_none());
+
+    // Phyrexian mana can be paid with life, if allowed.
+    assert!(pay("{G}{B}{W/P}", &PaymentOptions::default()).is_none());
+    let options = PaymentOptions {
+        life_budget: 2,
+        ..Default::default()
+    };
+    assert_eq!(pay("{G}{B}{W/P}", &options).unwrap().life, 2);
+
+    // Holding up the forest
+    let (forest, swamp): (Vec<_>, Vec<_>) = battlefield
+        .objects
+        .iter()
+        .partition(|o| o.card.card.name() == "Forest");
+    let options = PaymentOptions {
+        excluded: vec![forest[0].id()],
+        ..Default::default()
+    };
+    assert_eq!(pay("{1}", &options).unwrap().tapped(), vec![swamp[0].id()]);
+    assert!(pay("{G}", &options).is_none());
+}
+
+#[test]
+fn test_same_name_sources() {
+    use crate::{
+        card::{
+            abilities::{Ability, ManaProduction},
+            mana_cost::ManaCost,
+            test_card,
+            types::{LandProperties, Type},
+        },
+        game_play::{
+            agent::Goldfish,
+            layers::{Affected, Duration, Modification},
+            player::PlayerState,
+        },
+    };
+
+    let wastes = || {
+        test_card(
+            "Wastes",
+            Type::Land(LandProperties {
+                is_basic: true,
+                subtypes: Vec::new(),
+            }),
+            vec![Ability::Mana(ManaAbility::tap(ManaProduction::colorless()))],
+        )
+    };
+    let mut battlefield = Battlefield::new(vec![PlayerState::new(0, vec![wastes(), wastes()])]);
+    let player = battlefield.players[0].id;
+    while let Some(card) = battlefield.players[0].zones.library.pop_front() {
+        battlefield.play_land(card, &mut Goldfish);
+    }
+    let cost: ManaCost = "{U}".parse().unwrap();
+    let options = PaymentOptions::default();
+    assert_eq!(
+        find_payment(&battlefield, player, cost.as_ref(), &options),
+        None
+    );
+
+    // Only one of the lands with the same name can pay for {U}.
+    let id = battlefield.objects[1].id();
+    battlefield.add_continuous_effect(
+        Affected::Objects(vec![id]),
+        Modification::AddAbility(Box::new(Ability::Mana(ManaAbility::tap(
+            ManaProduction::blue(),
+        )))),
+        Duration::EndOfTurn,
+    );
+    let plan = find_payment(&battlefield, player, cost.as_ref(), &options).unwrap();
+    assert_eq!(plan.tapped(), vec![id]);
+}
+
+#[test]
+fn test_filter_land() {
+    use crate::{
+        card::{
+            abilities::{AbilityCost, ManaProduction},
+            mana_cost::ManaCost,
+            test_card,
+            types::{LandProperties, Type},
+        },
+        game_play::{agent::Goldfish, player::PlayerState},
+    };
+
+    let land = |name: &str, ability: ManaAbility| {
+        test_card(
+            name,
+            Type::Land(LandProperties {
+                is_basic: false,
+                subtypes: Vec::new(),
+            }),
+            vec![crate::card::abilities::Ability::Mana(ability)],
+        )
+    };
+    // "{B/R}, {T}: Add {B}{B}"
+    let filter = ManaAbility {
+        cost: AbilityCost {
+            tap: true,
+            mana_cost: Some("{B/R}".parse().unwrap()),
+            ..Default::default()
+        },
+        ..ManaAbility::tap(ManaProduction::Multiple {
+            units: vec![Unit::Black, Unit::Black],
+        })
+    };
+    let deck = vec![
+        land("Graven Cairns", filter),
+        land("Swamp", ManaAbility::tap(ManaProduction::black())),
+    ];
+    let mut battlefield = Battlefield::new(vec![PlayerState::new(0, deck)]);
+    let player = battlefield.players[0].id;
+    let play = |battlefield: &mut Battlefield, name: &str| {
+        let library = &mut battlefield.players[0].zones.library;
+        let i = library.iter().position(|c| c.card.name() == name).unwrap();
+        let card = library.remove(i).unwrap();
+        battlefield.play_land(card, &mut Goldfish);
+    };
+    play(&mut battlefield, "Graven Cairns");
+    let cost: ManaCost = "{B}".parse().unwrap();
+
+    // The filter land's mana cannot pay for its own cost.
+    let options = PaymentOptions::default();
+    assert_eq!(
+        find_payment(&battlefield, player, cost.as_ref(), &options),
+        None
+    );
+
+    play(&mut battlefield, "Swamp");
+    let cost: ManaCost = "{B}{B}".parse().unwrap();
+    let plan = find_payment(&battlefield, player, cost.as_ref(), &options).unwrap();
+    assert_eq!(plan.activations.len(), 2);
+    assert!(plan.leftover.is_empty());
+    for activation in &plan.activations {
+        battlefield
+            .activate_mana_ability(activation.source, activation.ability, &activation.produce)
+            .unwrap();
+    }
+    let pool = &mut battlefield.player_mut(player).mana_pool;
+    assert_eq!(pool.pay(cost.as_ref(), 0, 0, None), Ok(0));
+    assert!(pool.is_empty());
+}
+
+#[test]
+fn test_minimize_tapped() {
+    use crate::{
+        card::{
+            abilities::{Ability, ManaProduction},
+            mana_cost::ManaCost,
+            test_card,
+            types::{LandProperties, Type},
+        },
+        game_play::{agent::Goldfish, player::PlayerState},
+    };
+
+    let land = |name: &str, produce: Vec<ManaProduction>| {
+        test_card(
+            name,
+            Type::Land(LandProperties {
+                is_basic: false,
+                subtypes: Vec::new(),
+            }),
+            produce
+                .into_iter()
+                .map(|p| Ability::Mana(ManaAbility::tap(p)))
+                .collect(),
+        )
+    };
+    // Also producing colorless, so it is tried after the Forests.
+    let double_green = ManaProduction::Multiple {
+        units: vec![Unit::Green, Unit::Green],
+    };
+    let deck = vec![
+        land("Forest", vec![ManaProduction::green()]),
+        land("Forest", vec![ManaProduction::green()]),
+        land(
+            "Double Forest",
+            vec![double_green, ManaProduction::colorless()],
+        ),
+    ];
+    let mut battlefield = Battlefield::new(vec![PlayerState::new(0, deck)]);
+    let player = battlefield.players[0].id;
+    while let Some(card) = battlefield.players[0].zones.library.pop_front() {
+        battlefield.play_land(card, &mut Goldfish);
+    }
+    let cost: ManaCost = "{G}{G}".parse().unwrap();
+
+    // The first payment found taps both Forests.
+    let options = PaymentOptions::default();
+    let plan = find_payment(&battlefield, player, cost.as_ref(), &options).unwrap();
+    assert_eq!(plan.tapped().len(), 2);
+
+    let options = PaymentOptions {
+        minimize_tapped: true,
+        ..Default::default()
+    };
+    let plan = find_payment(&battlefield, player, cost.as_ref(), &options).unwrap();
+    let double_forest = battlefield
+        .objects
+        .iter()
+        .find(|o| o.card.card.name() == "Double Forest")
+        .unwrap();
+    assert_eq!(plan.tapped(), vec![double_forest.id()]);
+    assert!(plan.leftover.is_empty());
+}
+
+#[test]
+fn test_snow() {
+    use crate::{
+        card::{
+            Card, CardFace,
+            abilities::{Ability, ManaProduction},
+            mana_cost::ManaCost,
+            test_face,
+            types::{LandProperties, Type},
+        },
+        game_play::{agent::Goldfish, player::PlayerState},
+        mana::pool::CannotPay,
+    };
+
+    let forest = |name: &str, is_snow: bool| {
+        Card::Single(CardFace {
+            is_snow,
+            ..test_face(
+                name,
+                Type::Land(LandProperties {
+                    is_basic: true,
+                    subtypes: Vec::new(),
+                }),
+                vec![Ability::Mana(ManaAbility::tap(ManaProduction::green()))],
+            )
+        })
+    };
+    let deck = vec![
+        forest("Snow-Covered Forest", true),
+        forest("Snow-Covered Forest", true),
+    ];
+    let mut battlefield = Battlefield::new(vec![PlayerState::new(0, deck)]);
+    let player = battlefield.players[0].id;
+    while let Some(card) = battlefield.players[0].zones.library.pop_front() {
+        battlefield.play_land(card, &mut Goldfish);
+    }
+    let cost: ManaCost = "{S}{G}".parse().unwrap();
+    let options = PaymentOptions::default();
+    let plan = find_payment(&battlefield, player, cost.as_ref(), &options).unwrap();
+    assert_eq!(plan.tapped().len(), 2);
+    for activation in &plan.activations {
+        battlefield
+            .activate_mana_ability(activation.source, activation.ability, &activation.produce)
+            .unwrap();
+    }
+    let pool = &mut battlefield.player_mut(player).mana_pool;
+    assert!(pool.mana.iter().all(|m| m.snow));
+    assert_eq!(pool.pay(cost.as_ref(), 0, 0, None), Ok(0));
+    assert!(pool.is_empty());
+
+    // Mana from a basic Forest does not pay for {S}.
+    pool.add(Mana::new(Unit::Green));
+    let cost: ManaCost = "{S}".parse().unwrap();
+    assert_eq!(pool.pay(cost.as_ref(), 0, 0, None), Err(CannotPay));
+}
+
+#[test]
+fn test_spend_restriction() {
+    use crate::{
+        card::{
+            abilities::{Ability, ManaProduction},
+            mana_cost::ManaCost,
+            test_face,
+            types::{CreatureProperties, LandProperties, Power, Toughness, Type},
+        },
+        game_play::{agent::Goldfish, player::PlayerState},
+        mana::pool::{CannotPay, SpendRestriction},
+    };
+
+    // "{T}: Add {G}. Spend this mana only to cast a creature spell."
+    let ability = ManaAbility {
+        restriction: Some(SpendRestriction::CreatureSpells),
+        ..ManaAbility::tap(ManaProduction::green())
+    };
+    let land = LandProperties {
+        is_basic: false,
+        subtypes: Vec::new(),
+    };
+    let cavern = test_face("Cavern", Type::Land(land), vec![Ability::Mana(ability)]);
+    let bear = CreatureProperties {
+        subtypes: Vec::new(),
+        power: Power::Value(2),
+        toughness: Toughness::Value(2),
+    };
+    let mdfc = Card::ModalDoubleFaced {
+        front: test_face("Bear", Type::Creature(bear), Vec::new()),
+        back: test_face("Growth", Type::Instant, Vec::new()),
+    };
+    let mut battlefield = Battlefield::new(vec![PlayerState::new(0, vec![Card::Single(cavern)])]);
+    let player = battlefield.players[0].id;
+    let card = battlefield.players[0].zones.library.pop_front().unwrap();
+    battlefield.play_land(card, &mut Goldfish);
+    let cost: ManaCost = "{G}".parse().unwrap();
+
+    // The mana pays for the creature face, but not the instant face or an ability.
+    let payment = |battlefield: &Battlefield, spell| {
+        let options = PaymentOptions {
+            spell,
+            ..Default::default()
+        };
+        find_payment(battlefield, player, cost.as_ref(), &options)
+    };
+    let plan = payment(&battlefield, Some((&mdfc, CastFace::Face(0)))).unwrap();
+    assert!(payment(&battlefield, Some((&mdfc, CastFace::Face(1)))).is_none());
+    assert!(payment(&battlefield, None).is_none());
+
+    let activation = &plan.activations[0];
+    battlefield
+        .activate_mana_ability(activation.source, activation.ability, &activation.produce)
+        .unwrap();
+    let pool = &mut battlefield.player_mut(player).mana_pool;
+    assert_eq!(
+        pool.mana[0].restriction,
+        Some(SpendRestriction::CreatureSpells)
+    );
+    let instant = Some((&mdfc, CastFace::Face(1)));
+    assert_eq!(pool.pay(cost.as_ref(), 0, 0, instant), Err(CannotPay));
+    let creature = Some((&mdfc, CastFace::Face(0)));
+    assert_eq!(pool.pay(cost.as_ref(), 0, 0, creature), Ok(0));
+    assert!(pool.is_empty());
+}
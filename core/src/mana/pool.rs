@@ -0,0 +1,191 @@
+use crate::card::{
+    Card, CastFace,
+    mana_cost::{ManaCostRef, Payment, Unit},
+};
+
+/// Mana which has been produced, but not spent yet.
+/// It empties at the end of each step.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ManaPool {
+    pub mana: Vec<Mana>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mana {
+    pub unit: Unit,
+    pub restriction: Option<SpendRestriction>,
+    // Produced by a snow permanent
+    pub snow: bool,
+}
+
+impl Mana {
+    pub fn new(unit: Unit) -> Self {
+        Self {
+            unit,
+            restriction: None,
+            snow: false,
+        }
+    }
+
+    // Whether this mana can pay for one mana of the given unit (e.g. {G} or {S}).
+    pub fn pays(&self, unit: &Unit) -> bool {
+        match unit {
+            Unit::Snow => self.snow,
+            unit => self.unit == *unit,
+        }
+    }
+}
+
+// What mana from some sources can be spent on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpendRestriction {
+    CreatureSpells,
+}
+
+impl SpendRestriction {
+    // `spell` is the card and the face(s) being cast,
+    // or `None` when paying for something other than a spell (e.g. an ability).
+    pub fn allows(self, spell: Option<(&Card, CastFace)>) -> bool {
+        match self {
+            Self::CreatureSpells => spell
+                .is_some_and(|(card, face)| card.cast_faces(face).iter().any(|f| f.is_creature())),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CannotPay;
+
+impl ManaPool {
+    pub fn add(&mut self, mana: Mana) {
+        self.mana.push(mana);
+    }
+
+    pub fn empty(&mut self) {
+        self.mana.clear();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.mana.is_empty()
+    }
+
+    // Spends mana from the pool (and up to `life_budget` life for Phyrexian pips)
+    // to pay the cost. Returns the life to pay.
+    // Nothing is spent if the pool cannot pay the whole cost.
+    pub fn pay(
+        &mut self,
+        cost: ManaCostRef,
+        x: u8,
+        life_budget: u32,
+        spell: Option<(&Card, CastFace)>,
+    ) -> Result<u32, CannotPay> {
+        let mut requirements = Vec::new();
+        let mut generic = 0;
+        for pip in cost.pips {
+            match &pip.payment_options(x)[..] {
+                [Payment::Generic { amount }] => generic += u32::from(*amount),
+                options => requirements.push(options.to_vec()),
+            }
+        }
+        let usable: Vec<bool> = self
+            .mana
+            .iter()
+            .map(|m| m.restriction.is_none_or(|r| r.allows(spell)))
+            .collect();
+        let mut spent = vec![false; self.mana.len()];
+        let life = self
+            .search(&requirements, generic, &usable, &mut spent, life_budget)
+            .ok_or(CannotPay)?;
+
+        let mut spent = spent.into_iter();
+        self.mana.retain(|_| !spent.next().unwrap_or(false));
+        Ok(life)
+    }
+
+    // Restricted mana is spent first since it is less useful.
+    fn search(
+        &self,
+        requirements: &[Vec<Payment>],
+        generic: u32,
+        usable: &[bool],
+        spent: &mut [bool],
+        life_budget: u32,
+    ) -> Option<u32> {
+        let mut available: Vec<usize> = (0..self.mana.len())
+            .filter(|i| usable[*i] && !spent[*i])
+            .collect();
+        available.sort_by_key(|i| self.mana[*i].restriction.is_none());
+
+        let Some((options, rest)) = requirements.split_first() else {
+            if available.len() < generic as usize {
+                return None;
+            }
+            for i in available.into_iter().take(generic as usize) {
+                spent[i] = true;
+            }
+            return Some(0);
+        };
+        for option in options {
+            match option {
+                Payment::Life { amount } => {
+                    let amount = u32::from(*amount);
+                    if amount <= life_budget
+                        && let Some(life) =
+                            self.search(rest, generic, usable, spent, life_budget - amount)
+                    {
+                        return Some(life + amount);
+                    }
+                }
+                Payment::Generic { amount } => {
+                    let generic = generic + u32::from(*amount);
+                    if let Some(life) = self.search(rest, generic, usable, spent, life_budget) {
+                        return Some(life);
+                    }
+                }
+                Payment::Mana(unit) => {
+                    let mut tried: Vec<&Mana> = Vec::new();
+                    for &i in &available {
+                        let mana = &self.mana[i];
+                        if !mana.pays(unit) || tried.contains(&mana) {
+                            continue;
+                        }
+                        tried.push(mana);
+                        spent[i] = true;
+                        if let Some(life) = self.search(rest, generic, usable, spent, life_budget) {
+                            return Some(life);
+                        }
+                        spent[i] = false;
+                    }
+                }
+            }
+        }
+        None
+    }
+}
+
+#[test]
+fn test_pay_from_pool() {
+    use crate::card::mana_cost::ManaCost;
+
+    let mut pool = ManaPool::default();
+    for unit in [Unit::Black, Unit::Green, Unit::Red] {
+        pool.add(Mana::new(unit));
+    }
+    let cost: ManaCost = "{B}{B/G}".parse().unwrap();
+    assert_eq!(pool.pay(cost.as_ref(), 0, 0, None), Ok(0));
+    assert_eq!(pool.mana, vec![Mana::new(Unit::Red)]);
+
+    let cost: ManaCost = "{1}{W/P}".parse().unwrap();
+    assert_eq!(pool.pay(cost.as_ref(), 0, 0, None), Err(CannotPay));
+    assert_eq!(pool.mana.len(), 1, "Nothing is spent");
+    assert_eq!(pool.pay(cost.as_ref(), 0, 2, None), Ok(2));
+    assert!(pool.is_empty());
+
+    pool.add(Mana {
+        unit: Unit::Green,
+        restriction: Some(SpendRestriction::CreatureSpells),
+        snow: false,
+    });
+    let cost: ManaCost = "{G}".parse().unwrap();
+    assert_eq!(pool.pay(cost.as_ref(), 0, 0, None), Err(CannotPay));
+}
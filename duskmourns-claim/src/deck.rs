@@ -1,66 +1,74 @@
-//! Here is the deck list:
-//! ```ignore
-//! Deck
-//! 4 Cease // Desist (MKM) 246
-//! 3 Mountain (SLD) 1471
-//! 4 Twinmaw Stormbrood (TDM) 232
-//! 4 Virtue of Persistence (WOE) 115
-//! 4 Roaring Furnace // Steaming Sauna (DSK) 230
-//! 1 Swamp (SLD) 1470
-//! 4 Trumpeting Carnosaur (LCI) 171
-//! 2 Unholy Annex // Ritual Chamber (DSK) 118
-//! 4 Glassworks // Shattered Yard (DSK) 137
-//! 4 Duskmourn's Claim (OM1) 55
-//! 4 Geological Appraiser (LCI) 150
-//! 4 Conduit Pylons (OTJ) 254
-//! 3 Crystal Grotto (WOE) 254
-//! 4 Hidden Grotto (BLB) 254
-//! 4 Temple of Malice (FDN) 701
-//! 4 Raucous Theater (MKM) 266
-//! 3 Blazemire Verge (DSK) 256
-//! ```
-
 use mtg_lib_core::{
     card::{
         Card, CardFace,
         abilities::{
-            Ability, AbilityCost, EndStepAbility, EntersAbility, ManaAbility, ManaProduction,
+            Ability, AbilityCost, ActivationCondition, Effect, ManaAbility, ManaProduction,
+            PermanentFilter, TargetSpec, TriggerCondition, TriggeredAbility,
         },
         color::Color,
-        mana_cost::{ManaCost, Pip, Unit},
+        decklist::DeckList,
+        registry::CardRegistry,
         types::{
             CreatureProperties, CreatureSubtypes, EnchantmentProperties, EnchantmentSubtypes,
             LandProperties, LandSubtypes, Power, Toughness, Type,
         },
     },
-    game_play::battlefield::{Battlefield, Event},
+    game_play::{
+        battlefield::Battlefield,
+        player::PlayerId,
+        target::{Resolution, Target},
+    },
 };
 
+// Exported from MTG Arena
+pub const DECK_LIST: &str = "\
+Deck
+4 Cease // Desist (MKM) 246
+3 Mountain (SLD) 1471
+4 Twinmaw Stormbrood (TDM) 232
+4 Virtue of Persistence (WOE) 115
+4 Roaring Furnace // Steaming Sauna (DSK) 230
+1 Swamp (SLD) 1470
+4 Trumpeting Carnosaur (LCI) 171
+2 Unholy Annex // Ritual Chamber (DSK) 118
+4 Glassworks // Shattered Yard (DSK) 137
+4 Duskmourn's Claim (OM1) 55
+4 Geological Appraiser (LCI) 150
+4 Conduit Pylons (OTJ) 254
+3 Crystal Grotto (WOE) 254
+4 Hidden Grotto (BLB) 254
+4 Temple of Malice (FDN) 701
+4 Raucous Theater (MKM) 266
+3 Blazemire Verge (DSK) 256
+";
+
 pub fn deck() -> Vec<Card> {
+    DECK_LIST
+        .parse::<DeckList>()
+        .and_then(|list| list.main_deck_cards(&registry()))
+        .expect("Deck list matches the card definitions")
+}
+
+// Definitions of the cards in the deck.
+pub fn registry() -> CardRegistry {
     let cease = CardFace {
         name: "Cease".into(),
-        mana_cost: Some(ManaCost {
-            pips: vec![
-                Pip::Single(Unit::Generic { amount: 1 }),
-                Pip::Hybrid(Unit::Black, Unit::Green),
-            ],
-        }),
+        mana_cost: Some("{1}{B/G}".parse().unwrap()),
         color: Color::golgari(),
         is_legendary: false,
+        is_snow: false,
         type_line: Type::Instant,
-        abilities: vec![Ability::Other(cease_ability)],
+        abilities: vec![Ability::Spell(Effect::Other {
+            targets: vec![TargetSpec::Player],
+            resolve: cease_ability,
+        })],
     };
     let desist = CardFace {
         name: "Desist".into(),
-        mana_cost: Some(ManaCost {
-            pips: vec![
-                Pip::Single(Unit::Generic { amount: 4 }),
-                Pip::Hybrid(Unit::Green, Unit::White),
-                Pip::Hybrid(Unit::Green, Unit::White),
-            ],
-        }),
+        mana_cost: Some("{4}{G/W}{G/W}".parse().unwrap()),
         color: Color::selesnya(),
         is_legendary: false,
+        is_snow: false,
         type_line: Type::Sorcery,
         abilities: Vec::new(), // TODO
     };
@@ -68,14 +76,10 @@ pub fn deck() -> Vec<Card> {
 
     let twinmaw_stormbrood = CardFace {
         name: "Twinmaw Stormbrood".into(),
-        mana_cost: Some(ManaCost {
-            pips: vec![
-                Pip::Single(Unit::Generic { amount: 5 }),
-                Pip::Single(Unit::White),
-            ],
-        }),
+        mana_cost: Some("{5}{W}".parse().unwrap()),
         color: Color::white(),
         is_legendary: false,
+        is_snow: false,
         type_line: Type::Creature(CreatureProperties {
             subtypes: vec![CreatureSubtypes::Dragon],
             power: Power::Value(5),
@@ -83,20 +87,19 @@ pub fn deck() -> Vec<Card> {
         }),
         abilities: vec![
             Ability::Flying,
-            Ability::Enters(EntersAbility::GainLife { amount: 5 }),
+            Ability::Triggered(TriggeredAbility {
+                condition: TriggerCondition::Enters,
+                effect: Effect::GainLife { amount: 5 },
+            }),
         ],
     };
     let charring_bite = CardFace {
         name: "Charring Bite".into(),
-        mana_cost: Some(ManaCost {
-            pips: vec![
-                Pip::Single(Unit::Generic { amount: 1 }),
-                Pip::Single(Unit::Red),
-            ],
-        }),
+        mana_cost: Some("{1}{R}".parse().unwrap()),
         type_line: Type::Sorcery,
         color: Color::red(),
         is_legendary: false,
+        is_snow: false,
         abilities: Vec::new(), // TODO
     };
     let stormbrood = Card::Omen {
@@ -106,67 +109,55 @@ pub fn deck() -> Vec<Card> {
 
     let roaring_furnace = CardFace {
         name: "Roaring Furnace".into(),
-        mana_cost: Some(ManaCost {
-            pips: vec![
-                Pip::Single(Unit::Generic { amount: 1 }),
-                Pip::Single(Unit::Red),
-            ],
-        }),
+        mana_cost: Some("{1}{R}".parse().unwrap()),
         type_line: Type::Enchantment(EnchantmentProperties {
             subtypes: vec![EnchantmentSubtypes::Room],
         }),
         color: Color::red(),
         is_legendary: false,
+        is_snow: false,
         abilities: Vec::new(), // TODO
     };
     let steaming_sauna = CardFace {
         name: "Steaming Sauna".into(),
-        mana_cost: Some(ManaCost {
-            pips: vec![
-                Pip::Single(Unit::Generic { amount: 3 }),
-                Pip::Single(Unit::Blue),
-                Pip::Single(Unit::Blue),
-            ],
-        }),
+        mana_cost: Some("{3}{U}{U}".parse().unwrap()),
         type_line: Type::Enchantment(EnchantmentProperties {
             subtypes: vec![EnchantmentSubtypes::Room],
         }),
         color: Color::blue(),
         is_legendary: false,
+        is_snow: false,
         abilities: vec![
             // TODO: no max hand size
-            Ability::EndStep(EndStepAbility::Other(steaming_sauna_ability)),
+            Ability::Triggered(TriggeredAbility {
+                condition: TriggerCondition::EndStep,
+                effect: Effect::Other {
+                    targets: Vec::new(),
+                    resolve: steaming_sauna_ability,
+                },
+            }),
         ],
     };
     let furnace_sauna = Card::Split(roaring_furnace, steaming_sauna);
 
     let virtue_of_persistence = CardFace {
         name: "Virtue of Persistence".into(),
-        mana_cost: Some(ManaCost {
-            pips: vec![
-                Pip::Single(Unit::Generic { amount: 5 }),
-                Pip::Single(Unit::Black),
-                Pip::Single(Unit::Black),
-            ],
-        }),
+        mana_cost: Some("{5}{B}{B}".parse().unwrap()),
         type_line: Type::Enchantment(EnchantmentProperties {
             subtypes: Vec::new(),
         }),
         color: Color::black(),
         is_legendary: false,
+        is_snow: false,
         abilities: Vec::new(), // TODO
     };
     let locthwain_scorn = CardFace {
         name: "Locthwain Scorn".into(),
-        mana_cost: Some(ManaCost {
-            pips: vec![
-                Pip::Single(Unit::Generic { amount: 1 }),
-                Pip::Single(Unit::Black),
-            ],
-        }),
+        mana_cost: Some("{1}{B}".parse().unwrap()),
         type_line: Type::Sorcery,
         color: Color::black(),
         is_legendary: false,
+        is_snow: false,
         abilities: Vec::new(), // TODO
     };
     let virtue = Card::Adventure {
@@ -176,15 +167,10 @@ pub fn deck() -> Vec<Card> {
 
     let carnosaur = Card::Single(CardFace {
         name: "Trumpeting Carnosaur".into(),
-        mana_cost: Some(ManaCost {
-            pips: vec![
-                Pip::Single(Unit::Generic { amount: 4 }),
-                Pip::Single(Unit::Red),
-                Pip::Single(Unit::Red),
-            ],
-        }),
+        mana_cost: Some("{4}{R}{R}".parse().unwrap()),
         color: Color::red(),
         is_legendary: false,
+        is_snow: false,
         type_line: Type::Creature(CreatureProperties {
             subtypes: vec![CreatureSubtypes::Dinosaur],
             power: Power::Value(7),
@@ -192,56 +178,50 @@ pub fn deck() -> Vec<Card> {
         }),
         abilities: vec![
             Ability::Trample,
-            Ability::Enters(EntersAbility::Discover { amount: 5 }),
+            Ability::Triggered(TriggeredAbility {
+                condition: TriggerCondition::Enters,
+                effect: Effect::Discover { amount: 5 },
+            }),
             // TODO: discard burn ability
         ],
     });
 
     let glassworks = CardFace {
         name: "Glassworks".into(),
-        mana_cost: Some(ManaCost {
-            pips: vec![
-                Pip::Single(Unit::Generic { amount: 2 }),
-                Pip::Single(Unit::Red),
-            ],
-        }),
+        mana_cost: Some("{2}{R}".parse().unwrap()),
         type_line: Type::Enchantment(EnchantmentProperties {
             subtypes: vec![EnchantmentSubtypes::Room],
         }),
         color: Color::red(),
         is_legendary: false,
+        is_snow: false,
         abilities: Vec::new(), // TODO
     };
     let shattered_yard = CardFace {
         name: "Shattered Yard".into(),
-        mana_cost: Some(ManaCost {
-            pips: vec![
-                Pip::Single(Unit::Generic { amount: 4 }),
-                Pip::Single(Unit::Red),
-            ],
-        }),
+        mana_cost: Some("{4}{R}".parse().unwrap()),
         type_line: Type::Enchantment(EnchantmentProperties {
             subtypes: vec![EnchantmentSubtypes::Room],
         }),
         color: Color::red(),
         is_legendary: false,
-        abilities: vec![Ability::EndStep(EndStepAbility::Other(
-            shattered_yard_ability,
-        ))],
+        is_snow: false,
+        abilities: vec![Ability::Triggered(TriggeredAbility {
+            condition: TriggerCondition::EndStep,
+            effect: Effect::Other {
+                targets: Vec::new(),
+                resolve: shattered_yard_ability,
+            },
+        })],
     };
     let works_yard = Card::Split(glassworks, shattered_yard);
 
     let appraiser = Card::Single(CardFace {
         name: "Geological Appraiser".into(),
-        mana_cost: Some(ManaCost {
-            pips: vec![
-                Pip::Single(Unit::Generic { amount: 2 }),
-                Pip::Single(Unit::Red),
-                Pip::Single(Unit::Red),
-            ],
-        }),
+        mana_cost: Some("{2}{R}{R}".parse().unwrap()),
         color: Color::red(),
         is_legendary: false,
+        is_snow: false,
         type_line: Type::Creature(CreatureProperties {
             subtypes: vec![CreatureSubtypes::Human, CreatureSubtypes::Artificer],
             power: Power::Value(3),
@@ -249,59 +229,53 @@ pub fn deck() -> Vec<Card> {
         }),
         abilities: vec![
             // TODO: "if you cast it" restriction.
-            Ability::Enters(EntersAbility::Discover { amount: 3 }),
+            Ability::Triggered(TriggeredAbility {
+                condition: TriggerCondition::Enters,
+                effect: Effect::Discover { amount: 3 },
+            }),
         ],
     });
 
     let unholy_annex = CardFace {
         name: "Unholy Annex".into(),
-        mana_cost: Some(ManaCost {
-            pips: vec![
-                Pip::Single(Unit::Generic { amount: 2 }),
-                Pip::Single(Unit::Black),
-            ],
-        }),
+        mana_cost: Some("{2}{B}".parse().unwrap()),
         type_line: Type::Enchantment(EnchantmentProperties {
             subtypes: vec![EnchantmentSubtypes::Room],
         }),
         color: Color::black(),
         is_legendary: false,
+        is_snow: false,
         abilities: Vec::new(), // TODO
     };
     let ritual_chamber = CardFace {
         name: "Ritual Chamber".into(),
-        mana_cost: Some(ManaCost {
-            pips: vec![
-                Pip::Single(Unit::Generic { amount: 3 }),
-                Pip::Single(Unit::Black),
-                Pip::Single(Unit::Black),
-            ],
-        }),
+        mana_cost: Some("{3}{B}{B}".parse().unwrap()),
         type_line: Type::Enchantment(EnchantmentProperties {
             subtypes: vec![EnchantmentSubtypes::Room],
         }),
         color: Color::black(),
         is_legendary: false,
+        is_snow: false,
         abilities: Vec::new(), // TODO
     };
     let annex_chamber = Card::Split(unholy_annex, ritual_chamber);
 
     let duskmourns_claim = Card::Single(CardFace {
         name: "Duskmourn's Claim".into(),
-        mana_cost: Some(ManaCost {
-            pips: vec![
-                Pip::Single(Unit::Generic { amount: 2 }),
-                Pip::Single(Unit::Black),
-            ],
-        }),
+        mana_cost: Some("{2}{B}".parse().unwrap()),
         color: Color::black(),
         is_legendary: false,
+        is_snow: false,
         type_line: Type::Enchantment(EnchantmentProperties {
             subtypes: Vec::new(),
         }),
-        abilities: vec![Ability::EndStep(EndStepAbility::Other(
-            duskmourns_claim_ability,
-        ))],
+        abilities: vec![Ability::Triggered(TriggeredAbility {
+            condition: TriggerCondition::EndStep,
+            effect: Effect::Other {
+                targets: vec![TargetSpec::Player, TargetSpec::Player],
+                resolve: duskmourns_claim_ability,
+            },
+        })],
     });
 
     let mountain = Card::Single(CardFace {
@@ -309,17 +283,12 @@ pub fn deck() -> Vec<Card> {
         mana_cost: None,
         color: Color::colorless(),
         is_legendary: false,
+        is_snow: false,
         type_line: Type::Land(LandProperties {
             is_basic: true,
             subtypes: vec![LandSubtypes::Mountain],
         }),
-        abilities: vec![Ability::Mana(ManaAbility {
-            cost: AbilityCost {
-                tap: true,
-                mana_cost: None,
-            },
-            produce: ManaProduction::red(),
-        })],
+        abilities: vec![Ability::Mana(ManaAbility::tap(ManaProduction::red()))],
     });
 
     let swamp = Card::Single(CardFace {
@@ -327,17 +296,12 @@ pub fn deck() -> Vec<Card> {
         mana_cost: None,
         color: Color::colorless(),
         is_legendary: false,
+        is_snow: false,
         type_line: Type::Land(LandProperties {
             is_basic: true,
             subtypes: vec![LandSubtypes::Swamp],
         }),
-        abilities: vec![Ability::Mana(ManaAbility {
-            cost: AbilityCost {
-                tap: true,
-                mana_cost: None,
-            },
-            produce: ManaProduction::black(),
-        })],
+        abilities: vec![Ability::Mana(ManaAbility::tap(ManaProduction::black()))],
     });
 
     let conduit_pylons = Card::Single(CardFace {
@@ -345,27 +309,26 @@ pub fn deck() -> Vec<Card> {
         mana_cost: None,
         color: Color::colorless(),
         is_legendary: false,
+        is_snow: false,
         type_line: Type::Land(LandProperties {
             is_basic: false,
             subtypes: vec![LandSubtypes::Desert],
         }),
         abilities: vec![
-            Ability::Enters(EntersAbility::Surveil { amount: 1 }),
-            Ability::Mana(ManaAbility {
-                cost: AbilityCost {
-                    tap: true,
-                    mana_cost: None,
-                },
-                produce: ManaProduction::colorless(),
+            Ability::Triggered(TriggeredAbility {
+                condition: TriggerCondition::Enters,
+                effect: Effect::Surveil { amount: 1 },
             }),
+            Ability::Mana(ManaAbility::tap(ManaProduction::colorless())),
             Ability::Mana(ManaAbility {
                 cost: AbilityCost {
                     tap: true,
-                    mana_cost: Some(ManaCost {
-                        pips: vec![Pip::Single(Unit::Generic { amount: 1 })],
-                    }),
+                    mana_cost: Some("{1}".parse().unwrap()),
+                    ..Default::default()
                 },
                 produce: ManaProduction::any_color(),
+                condition: None,
+                restriction: None,
             }),
         ],
     });
@@ -375,27 +338,26 @@ pub fn deck() -> Vec<Card> {
         mana_cost: None,
         color: Color::colorless(),
         is_legendary: false,
+        is_snow: false,
         type_line: Type::Land(LandProperties {
             is_basic: false,
             subtypes: Vec::new(),
         }),
         abilities: vec![
-            Ability::Enters(EntersAbility::Scry { amount: 1 }),
-            Ability::Mana(ManaAbility {
-                cost: AbilityCost {
-                    tap: true,
-                    mana_cost: None,
-                },
-                produce: ManaProduction::colorless(),
+            Ability::Triggered(TriggeredAbility {
+                condition: TriggerCondition::Enters,
+                effect: Effect::Scry { amount: 1 },
             }),
+            Ability::Mana(ManaAbility::tap(ManaProduction::colorless())),
             Ability::Mana(ManaAbility {
                 cost: AbilityCost {
                     tap: true,
-                    mana_cost: Some(ManaCost {
-                        pips: vec![Pip::Single(Unit::Generic { amount: 1 })],
-                    }),
+                    mana_cost: Some("{1}".parse().unwrap()),
+                    ..Default::default()
                 },
                 produce: ManaProduction::any_color(),
+                condition: None,
+                restriction: None,
             }),
         ],
     });
@@ -405,27 +367,26 @@ pub fn deck() -> Vec<Card> {
         mana_cost: None,
         color: Color::colorless(),
         is_legendary: false,
+        is_snow: false,
         type_line: Type::Land(LandProperties {
             is_basic: false,
             subtypes: Vec::new(),
         }),
         abilities: vec![
-            Ability::Enters(EntersAbility::Surveil { amount: 1 }),
-            Ability::Mana(ManaAbility {
-                cost: AbilityCost {
-                    tap: true,
-                    mana_cost: None,
-                },
-                produce: ManaProduction::colorless(),
+            Ability::Triggered(TriggeredAbility {
+                condition: TriggerCondition::Enters,
+                effect: Effect::Surveil { amount: 1 },
             }),
+            Ability::Mana(ManaAbility::tap(ManaProduction::colorless())),
             Ability::Mana(ManaAbility {
                 cost: AbilityCost {
                     tap: true,
-                    mana_cost: Some(ManaCost {
-                        pips: vec![Pip::Single(Unit::Generic { amount: 1 })],
-                    }),
+                    mana_cost: Some("{1}".parse().unwrap()),
+                    ..Default::default()
                 },
                 produce: ManaProduction::any_color(),
+                condition: None,
+                restriction: None,
             }),
         ],
     });
@@ -435,20 +396,18 @@ pub fn deck() -> Vec<Card> {
         mana_cost: None,
         color: Color::colorless(),
         is_legendary: false,
+        is_snow: false,
         type_line: Type::Land(LandProperties {
             is_basic: false,
             subtypes: Vec::new(),
         }),
         abilities: vec![
-            Ability::Enters(EntersAbility::Tapped),
-            Ability::Enters(EntersAbility::Scry { amount: 1 }),
-            Ability::Mana(ManaAbility {
-                cost: AbilityCost {
-                    tap: true,
-                    mana_cost: None,
-                },
-                produce: ManaProduction::rakdos(),
+            Ability::EntersTapped,
+            Ability::Triggered(TriggeredAbility {
+                condition: TriggerCondition::Enters,
+                effect: Effect::Scry { amount: 1 },
             }),
+            Ability::Mana(ManaAbility::tap(ManaProduction::rakdos())),
         ],
     });
 
@@ -457,20 +416,18 @@ pub fn deck() -> Vec<Card> {
         mana_cost: None,
         color: Color::colorless(),
         is_legendary: false,
+        is_snow: false,
         type_line: Type::Land(LandProperties {
             is_basic: false,
             subtypes: vec![LandSubtypes::Mountain, LandSubtypes::Swamp],
         }),
         abilities: vec![
-            Ability::Enters(EntersAbility::Tapped),
-            Ability::Enters(EntersAbility::Surveil { amount: 1 }),
-            Ability::Mana(ManaAbility {
-                cost: AbilityCost {
-                    tap: true,
-                    mana_cost: None,
-                },
-                produce: ManaProduction::rakdos(),
+            Ability::EntersTapped,
+            Ability::Triggered(TriggeredAbility {
+                condition: TriggerCondition::Enters,
+                effect: Effect::Surveil { amount: 1 },
             }),
+            Ability::Mana(ManaAbility::tap(ManaProduction::rakdos())),
         ],
     });
 
@@ -479,150 +436,97 @@ pub fn deck() -> Vec<Card> {
         mana_cost: None,
         color: Color::colorless(),
         is_legendary: false,
+        is_snow: false,
         type_line: Type::Land(LandProperties {
             is_basic: false,
             subtypes: Vec::new(),
         }),
         abilities: vec![
+            Ability::Mana(ManaAbility::tap(ManaProduction::black())),
             Ability::Mana(ManaAbility {
-                cost: AbilityCost {
-                    tap: true,
-                    mana_cost: None,
-                },
-                produce: ManaProduction::black(),
-            }),
-            Ability::Mana(ManaAbility {
-                // TODO: this ability has the restriction
-                // "Activate only if you control a Swamp or a Mountain."
-                cost: AbilityCost {
-                    tap: true,
-                    mana_cost: None,
-                },
-                produce: ManaProduction::red(),
+                condition: Some(ActivationCondition::ControlsAny(vec![
+                    PermanentFilter::LandSubtype(LandSubtypes::Swamp),
+                    PermanentFilter::LandSubtype(LandSubtypes::Mountain),
+                ])),
+                ..ManaAbility::tap(ManaProduction::red())
             }),
         ],
     });
 
-    vec![
-        cease_desist.clone(),
-        cease_desist.clone(),
-        cease_desist.clone(),
+    [
         cease_desist,
-        stormbrood.clone(),
-        stormbrood.clone(),
-        stormbrood.clone(),
         stormbrood,
-        furnace_sauna.clone(),
-        furnace_sauna.clone(),
-        furnace_sauna.clone(),
         furnace_sauna,
-        virtue.clone(),
-        virtue.clone(),
-        virtue.clone(),
         virtue,
-        carnosaur.clone(),
-        carnosaur.clone(),
-        carnosaur.clone(),
         carnosaur,
-        works_yard.clone(),
-        works_yard.clone(),
-        works_yard.clone(),
         works_yard,
-        appraiser.clone(),
-        appraiser.clone(),
-        appraiser.clone(),
         appraiser,
-        annex_chamber.clone(),
         annex_chamber,
-        duskmourns_claim.clone(),
-        duskmourns_claim.clone(),
-        duskmourns_claim.clone(),
         duskmourns_claim,
-        mountain.clone(),
-        mountain.clone(),
         mountain,
         swamp,
-        conduit_pylons.clone(),
-        conduit_pylons.clone(),
-        conduit_pylons.clone(),
         conduit_pylons,
-        crystal_grotto.clone(),
-        crystal_grotto.clone(),
         crystal_grotto,
-        hidden_grotto.clone(),
-        hidden_grotto.clone(),
-        hidden_grotto.clone(),
         hidden_grotto,
-        temple_of_malice.clone(),
-        temple_of_malice.clone(),
-        temple_of_malice.clone(),
         temple_of_malice,
-        raucous_theater.clone(),
-        raucous_theater.clone(),
-        raucous_theater.clone(),
         raucous_theater,
-        blazemire_verge.clone(),
-        blazemire_verge.clone(),
         blazemire_verge,
     ]
+    .into_iter()
+    .collect()
 }
 
 // TODO: can also exile up to two cards from a single graveyard.
-// TODO: should be target player; need to figure out targeting.
-fn cease_ability(battlefield: &mut Battlefield) {
-    let player = battlefield.players.first_mut().unwrap();
-
-    player.life_total += 2;
-    if player.draw_a_card().is_ok() {
-        battlefield.log.push(Event::Draw(
-            player.zones.hand.last().expect("Just drew card").clone(),
-        ));
-    }
+fn cease_ability(battlefield: &mut Battlefield, resolution: &Resolution) {
+    let Some(player) = resolution.target_player(0) else {
+        return;
+    };
+    battlefield.gain_life(player, 2);
+    battlefield.draw_card(player).ok();
 }
 
-// TODO: should be target players
-fn duskmourns_claim_ability(battlefield: &mut Battlefield) {
-    let mut players = battlefield.players.iter_mut();
-    let player1 = players.next().unwrap();
-    let player2 = players.next().unwrap();
-
-    let card1 = player1.zones.library.pop_front();
-    let card2 = player2.zones.library.pop_front();
+fn duskmourns_claim_ability(battlefield: &mut Battlefield, resolution: &Resolution) {
+    let (Some(player1), Some(player2)) = (resolution.target_player(0), resolution.target_player(1))
+    else {
+        return;
+    };
 
-    if let Some(card) = card1 {
-        let life_lost = card.card.mana_value() as i32;
-        battlefield.log.push(Event::LostLife(player2.id, life_lost));
-        player2.life_total -= life_lost;
-        player1.zones.hand.push(card);
+    // Each player loses life equal to the mana value of the card revealed by the other,
+    // then puts the card they revealed into their hand.
+    let revealed = [
+        (battlefield.take_from_library(player1, 1).pop(), player2),
+        (battlefield.take_from_library(player2, 1).pop(), player1),
+    ];
+    for (card, other) in &revealed {
+        if let Some(card) = card {
+            battlefield.lose_life(*other, card.card.mana_value().into());
+        }
     }
-
-    if let Some(card) = card2 {
-        let life_lost = card.card.mana_value() as i32;
-        player1.life_total -= life_lost;
-        battlefield.log.push(Event::LostLife(player1.id, life_lost));
-        player2.zones.hand.push(card);
+    for (card, _) in revealed {
+        if let Some(card) = card {
+            battlefield.put_into_hand(card);
+        }
     }
 }
 
-// TODO: proper notion of opponent
-// TODO: notion of damage as opposed to life loss
-fn shattered_yard_ability(battlefield: &mut Battlefield) {
-    let opponents = battlefield.players.iter_mut().skip(1);
+fn shattered_yard_ability(battlefield: &mut Battlefield, resolution: &Resolution) {
+    let Some(source) = &resolution.source else {
+        return;
+    };
+    let opponents: Vec<PlayerId> = battlefield
+        .players
+        .iter()
+        .map(|p| p.id)
+        .filter(|id| *id != resolution.controller)
+        .collect();
 
     for player in opponents {
-        player.life_total -= 1;
-        battlefield.log.push(Event::LostLife(player.id, 1));
+        battlefield.deal_damage(source, Target::Player(player), 1, false);
     }
 }
 
-// TODO: notion of "you"
-fn steaming_sauna_ability(battlefield: &mut Battlefield) {
-    let player = battlefield.players.first_mut().unwrap();
-    if player.draw_a_card().is_ok() {
-        battlefield.log.push(Event::Draw(
-            player.zones.hand.last().expect("Just drew card").clone(),
-        ));
-    }
+fn steaming_sauna_ability(battlefield: &mut Battlefield, resolution: &Resolution) {
+    battlefield.draw_card(resolution.controller).ok();
 }
 
 #[test]
@@ -1,16 +1,13 @@
-use {
-    mtg_lib_core::{
-        card::{
-            Card,
-            abilities::{Ability, EndStepAbility},
-        },
-        game_play::{
-            OwnedCard,
-            battlefield::{Battlefield, Event},
-            player::PlayerState,
-        },
+use mtg_lib_core::{
+    card::Card,
+    game_play::{
+        ObjectId, OwnedCard,
+        agent::{Action, Agent, ScryChoice, SurveilChoice},
+        battlefield::Battlefield,
+        combat::Defender,
+        player::{PlayerId, PlayerState},
+        turn::Step,
     },
-    rand::seq::SliceRandom,
 };
 
 const APPRAISER_NAME: &str = "Geological Appraiser";
@@ -19,6 +16,120 @@ const CLAIM_NAME: &str = "Duskmourn's Claim";
 mod cast_spell;
 mod play_land;
 
+// Decisions made by the Duskmourn's Claim player.
+// The opponent is a goldfish, so it always passes.
+pub struct ClaimStrategy {
+    player: PlayerId,
+}
+
+impl Agent for ClaimStrategy {
+    fn keep_hand(&mut self, battlefield: &Battlefield, player: PlayerId, mulligans: usize) -> bool {
+        decide_to_keep(&battlefield.player(player).zones.hand, 7 - mulligans)
+    }
+
+    fn choose_cards_to_bottom(
+        &mut self,
+        battlefield: &Battlefield,
+        player: PlayerId,
+        amount: usize,
+    ) -> Vec<ObjectId> {
+        let hand = &battlefield.player(player).zones.hand;
+        let mut remaining: Vec<&OwnedCard> = hand.iter().collect();
+        let mut bottom = Vec::with_capacity(amount);
+        for _ in 0..amount {
+            let index = remaining
+                .iter()
+                .position(|c| {
+                    // Bottom a big spell
+                    c.card.mana_value() > 4
+                })
+                .or_else(|| {
+                    // Or an extra land
+                    remaining.iter().position(|c| c.card.is_land())
+                })
+                .unwrap_or_default();
+            bottom.push(remaining.remove(index).id);
+        }
+        bottom
+    }
+
+    fn choose_land(&mut self, battlefield: &Battlefield, player: PlayerId) -> Option<ObjectId> {
+        play_land::choose_land(battlefield, player)
+    }
+
+    fn choose_action(&mut self, battlefield: &Battlefield, player: PlayerId) -> Action {
+        // We never respond to anything on the stack.
+        if player != self.player || !battlefield.stack.is_empty() {
+            return Action::Pass;
+        }
+        if battlefield.can_play_land(player)
+            && let Some(index) = self.choose_land(battlefield, player)
+        {
+            return Action::PlayLand(index);
+        }
+        if battlefield.turn.step == Step::PrecombatMain
+            && let Some(action) = cast_spell::choose_spell(battlefield, player)
+        {
+            return action;
+        }
+        Action::Pass
+    }
+
+    // The goldfish has no blockers, so we attack with everything.
+    fn choose_attackers(
+        &mut self,
+        battlefield: &Battlefield,
+        player: PlayerId,
+    ) -> Vec<(ObjectId, Defender)> {
+        let opponent = Defender::Player(battlefield.next_player(player));
+        battlefield
+            .objects
+            .iter()
+            .map(|o| o.id())
+            .filter(|id| battlefield.can_attack(*id, opponent))
+            .map(|id| (id, opponent))
+            .collect()
+    }
+
+    fn choose_scry(
+        &mut self,
+        battlefield: &Battlefield,
+        _player: PlayerId,
+        cards: &[OwnedCard],
+    ) -> Vec<ScryChoice> {
+        let claim_in_play = play_land::claim_in_play(battlefield);
+        cards
+            .iter()
+            .map(|c| {
+                if play_land::decide_to_bottom(claim_in_play, &c.card) {
+                    ScryChoice::Bottom
+                } else {
+                    ScryChoice::Top
+                }
+            })
+            .collect()
+    }
+
+    fn choose_surveil(
+        &mut self,
+        battlefield: &Battlefield,
+        _player: PlayerId,
+        cards: &[OwnedCard],
+    ) -> Vec<SurveilChoice> {
+        let claim_in_play = play_land::claim_in_play(battlefield);
+        cards
+            .iter()
+            .map(|c| {
+                if play_land::decide_to_bottom(claim_in_play, &c.card) {
+                    SurveilChoice::Graveyard
+                } else {
+                    SurveilChoice::Top
+                }
+            })
+            .collect()
+    }
+}
+
 pub enum GameFlow {
     Continue,
     Victory,
@@ -43,70 +154,13 @@ pub fn initialize() -> Battlefield {
         .clone();
     let gold_fish_deck = vec![mountain; 60];
 
-    Battlefield {
-        players: vec![
-            PlayerState::new(0, player_deck),
-            PlayerState::new(1, gold_fish_deck),
-        ],
-        objects: Vec::new(),
-        log: Vec::new(),
-    }
-}
-
-pub fn opening_hand(battlefield: &mut Battlefield) {
-    let player = battlefield.players.first_mut().unwrap();
-
-    let mut n_keep = 7;
-    loop {
-        let mut hand = Vec::with_capacity(7);
-        for _ in 0..7 {
-            let card = player.zones.library.pop_front().unwrap();
-            battlefield.log.push(Event::Draw(card.clone()));
-            hand.push(card);
-        }
-        player.zones.hand = hand;
-        if decide_to_keep(&player.zones.hand, n_keep) {
-            break;
-        }
-        battlefield.log.push(Event::Mulligan(player.id));
-        mulligan(player);
-        n_keep -= 1;
-    }
-
-    // Bottom cards if we took mulligans
-    while n_keep < 7 {
-        let index = player
-            .zones
-            .hand
-            .iter()
-            .position(|o| {
-                // Bottom a big spell
-                o.card.mana_value() > 4
-            })
-            .or_else(|| {
-                // Or an extra land
-                player.zones.hand.iter().position(|o| o.card.is_land())
-            })
-            .unwrap_or_default();
-        let card = player.zones.hand.remove(index);
-        player.zones.library.push_back(card);
-        n_keep += 1;
-    }
+    Battlefield::new(vec![
+        PlayerState::new(0, player_deck),
+        PlayerState::new(1, gold_fish_deck),
+    ])
 }
 
-fn mulligan(player: &mut PlayerState) {
-    let mut library = Vec::with_capacity(60);
-    for card in player.zones.hand.drain(..) {
-        library.push(card);
-    }
-    for card in player.zones.library.drain(..) {
-        library.push(card);
-    }
-    library.shuffle(&mut rand::rng());
-    player.zones.library = library.into();
-}
-
-fn decide_to_keep(hand: &[OwnedCard], n_keep: u8) -> bool {
+fn decide_to_keep(hand: &[OwnedCard], n_keep: usize) -> bool {
     // Keep every hand; the heuristics I have makes things worse.
     // TODO: better mulligan heuristics.
     if n_keep < 8 {
@@ -138,12 +192,14 @@ fn decide_to_keep(hand: &[OwnedCard], n_keep: u8) -> bool {
 // Returns the number of turns to victory
 pub fn simulation_run() -> isize {
     let mut battlefield = initialize();
-    opening_hand(&mut battlefield);
+    let player = battlefield.players.first().unwrap().id;
+    let mut strategy = ClaimStrategy { player };
+    battlefield.draw_opening_hand(player, &mut strategy);
 
     let mut turn = 0;
     loop {
         turn += 1;
-        match turn_cycle(&mut battlefield) {
+        match turn_cycle(&mut battlefield, &mut strategy) {
             GameFlow::Continue => (),
             GameFlow::Victory => {
                 return turn;
@@ -155,74 +211,27 @@ pub fn simulation_run() -> isize {
     }
 }
 
-pub fn turn_cycle(battlefield: &mut Battlefield) -> GameFlow {
-    let player = battlefield.players.first_mut().unwrap();
-    let player_id = player.id;
-    battlefield.log.push(Event::StartTurn(player_id));
+pub fn turn_cycle(battlefield: &mut Battlefield, strategy: &mut ClaimStrategy) -> GameFlow {
+    // The opponent is a goldfish, so we only ever take our own turns.
+    battlefield.start_turn(strategy.player);
 
-    // Untap
-    for object in battlefield.objects.iter_mut() {
-        if object.tapped {
-            battlefield.log.push(Event::Untap(object.clone()));
+    let opponent = battlefield.next_player(strategy.player);
+    loop {
+        let Some(step) = battlefield.advance_step(strategy) else {
+            return GameFlow::Continue;
+        };
+
+        // State-based actions are checked as players receive priority.
+        // There is no priority during cleanup, unless an ability triggered.
+        if step.has_priority() || !battlefield.stack.is_empty() {
+            battlefield.play_priority(strategy);
         }
-        object.tapped = false;
-    }
 
-    // Draw for turn
-    if player.draw_a_card().is_err() {
-        return GameFlow::Loss;
-    }
-    battlefield.log.push(Event::Draw(
-        player
-            .zones
-            .hand
-            .last()
-            .expect("A card was just drawn")
-            .clone(),
-    ));
-
-    play_land::play_a_land(battlefield);
-    cast_spell::cast_spells(battlefield);
-
-    // End step
-    // TODO: it is probably more efficient to keep the end step triggers
-    // in the `Battlefield` object and just resolve them by iterating  over
-    // that list instead of iterating through all in-play objects each time.
-    let mut end_step_effects = Vec::new();
-    for object in &battlefield.objects {
-        match &object.card.card {
-            Card::Single(face) => {
-                push_end_step_abilities(&mut end_step_effects, &face.abilities);
-            }
-            Card::Split(left_face, right_face) => {
-                // TODO: need to keep track of which faces are unlocked somehow.
-                push_end_step_abilities(&mut end_step_effects, &left_face.abilities);
-                push_end_step_abilities(&mut end_step_effects, &right_face.abilities);
-            }
-            // TODO: should check other card kinds as well
-            _ => (),
+        if battlefield.player(strategy.player).has_lost {
+            return GameFlow::Loss;
         }
-    }
-    for effect in end_step_effects {
-        effect(battlefield);
-    }
-
-    // Check victory
-    if battlefield.players.get(1).unwrap().life_total <= 0 {
-        GameFlow::Victory
-    } else {
-        battlefield.log.push(Event::EndTurn(player_id));
-        GameFlow::Continue
-    }
-}
-
-fn push_end_step_abilities(
-    end_step_effects: &mut Vec<fn(&mut Battlefield)>,
-    abilities: &[Ability],
-) {
-    for ability in abilities {
-        if let Ability::EndStep(EndStepAbility::Other(ability)) = ability {
-            end_step_effects.push(*ability);
+        if battlefield.player(opponent).has_lost {
+            return GameFlow::Victory;
         }
     }
 }
@@ -3,72 +3,38 @@ use {
     mtg_lib_core::{
         card::{
             Card,
-            abilities::{Ability, EntersAbility, ManaProduction},
+            abilities::{Ability, Effect, TriggerCondition, TriggeredAbility},
             mana_cost::Unit,
             types::{LandSubtypes, Type},
         },
-        game_play::{
-            OwnedCard,
-            battlefield::{Battlefield, Event},
-            player::PlayerState,
-        },
+        game_play::{ObjectId, OwnedCard, battlefield::Battlefield, player::PlayerId},
     },
 };
 
 type CardProperty = fn(&OwnedCard) -> bool;
 
-pub fn play_a_land(battlefield: &mut Battlefield) {
+// The land in hand to play, if any.
+pub fn choose_land(battlefield: &Battlefield, player: PlayerId) -> Option<ObjectId> {
     // On turn 1 there are no other lands in play
     let is_turn_1 = !battlefield.objects.iter().any(|o| o.card.card.is_land());
 
-    let claim_in_play = battlefield
-        .objects
-        .iter()
-        .any(|o| o.card.card.primary_name() == Some(CLAIM_NAME));
+    let claim_in_play = claim_in_play(battlefield);
 
-    let player = battlefield.players.first_mut().unwrap();
-    let Some(card_to_play) = select_land_to_play(player, is_turn_1, claim_in_play) else {
-        return;
-    };
+    select_land_to_play(
+        &battlefield.player(player).zones.hand,
+        is_turn_1,
+        claim_in_play,
+    )
+}
 
-    if let Some(etb) = battlefield.play_land(card_to_play) {
-        match etb {
-            EntersAbility::Scry { amount } => {
-                // TODO: support multiple cards for scry
-                debug_assert_eq!(amount, 1);
-                let library = &mut battlefield.players.first_mut().unwrap().zones.library;
-                let Some(card) = library.pop_front() else {
-                    return;
-                };
-                if decide_to_bottom(claim_in_play, &card.card) {
-                    battlefield.log.push(Event::ScryBottom(card.clone()));
-                    library.push_back(card);
-                } else {
-                    battlefield.log.push(Event::ScryTop(card.clone()));
-                    library.push_front(card);
-                }
-            }
-            EntersAbility::Surveil { amount } => {
-                // TODO: support multiple cards for surveil
-                debug_assert_eq!(amount, 1);
-                let player = battlefield.players.first_mut().unwrap();
-                let Some(card) = player.zones.library.pop_front() else {
-                    return;
-                };
-                if decide_to_bottom(claim_in_play, &card.card) {
-                    battlefield.log.push(Event::SurveilYard(card.clone()));
-                    player.zones.graveyard.push(card);
-                } else {
-                    battlefield.log.push(Event::SurveilTop(card.clone()));
-                    player.zones.library.push_front(card);
-                }
-            }
-            _ => (),
-        }
-    }
+pub fn claim_in_play(battlefield: &Battlefield) -> bool {
+    battlefield
+        .objects
+        .iter()
+        .any(|o| o.card.card.primary_name() == Some(CLAIM_NAME))
 }
 
-fn decide_to_bottom(claim_in_play: bool, card: &Card) -> bool {
+pub fn decide_to_bottom(claim_in_play: bool, card: &Card) -> bool {
     // - Top lands if Claim not in play, bottom otherwise
     // - Top big spells if Claim is in play, bottom otherwise
     // - Always top Claim
@@ -82,16 +48,11 @@ fn decide_to_bottom(claim_in_play: bool, card: &Card) -> bool {
 }
 
 fn select_land_to_play(
-    player: &mut PlayerState,
+    hand: &[OwnedCard],
     is_turn_1: bool,
     claim_in_play: bool,
-) -> Option<OwnedCard> {
-    let mut lands: Vec<&OwnedCard> = player
-        .zones
-        .hand
-        .iter()
-        .filter(|c| c.card.is_land())
-        .collect();
+) -> Option<ObjectId> {
+    let mut lands: Vec<&OwnedCard> = hand.iter().filter(|c| c.card.is_land()).collect();
 
     let preference: (CardProperty, CardProperty, CardProperty) = if is_turn_1 {
         // Prefer tapped lands on turn 1
@@ -106,14 +67,7 @@ fn select_land_to_play(
 
     lands.sort_unstable_by_key(|c| (!(preference.0)(c), !(preference.1)(c), !(preference.2)(c)));
 
-    let name_to_play = lands.first().and_then(|c| c.card.primary_name())?;
-    let index_to_play = player
-        .zones
-        .hand
-        .iter()
-        .position(|c| c.card.primary_name() == Some(name_to_play))
-        .expect("Card is known to be in hand");
-    Some(player.zones.hand.swap_remove(index_to_play))
+    lands.first().map(|c| c.id)
 }
 
 fn enters_tapped(card: &OwnedCard) -> bool {
@@ -125,21 +79,18 @@ fn enters_untapped(card: &OwnedCard) -> bool {
 }
 
 fn has_scry_etb(card: &OwnedCard) -> bool {
+    let is_scry_etb = |a: &Ability| {
+        matches!(
+            a,
+            Ability::Triggered(TriggeredAbility {
+                condition: TriggerCondition::Enters,
+                effect: Effect::Scry { .. } | Effect::Surveil { .. },
+            })
+        )
+    };
     match &card.card {
-        Card::Single(face) => face.abilities.iter().any(|a| {
-            matches!(
-                a,
-                Ability::Enters(EntersAbility::Scry { .. })
-                    | Ability::Enters(EntersAbility::Surveil { .. })
-            )
-        }),
-        Card::Adventure { primary, .. } => primary.abilities.iter().any(|a| {
-            matches!(
-                a,
-                Ability::Enters(EntersAbility::Scry { .. })
-                    | Ability::Enters(EntersAbility::Surveil { .. })
-            )
-        }),
+        Card::Single(face) => face.abilities.iter().any(is_scry_etb),
+        Card::Adventure { primary, .. } => primary.abilities.iter().any(is_scry_etb),
         _ => false,
     }
 }
@@ -165,7 +116,7 @@ fn produces_black(card: &OwnedCard) -> bool {
         if ability.cost.mana_cost.is_some() {
             return false;
         }
-        matches!(&ability.produce, ManaProduction::Single { possible } if possible.contains(&Unit::Black))
+        ability.produce.units().contains(&Unit::Black)
     })
 }
 
@@ -196,55 +147,23 @@ fn test_select_land() {
     let player = battlefield.players.first_mut().unwrap();
     let id = player.id;
     let hand = &mut player.zones.hand;
-    hand.push(OwnedCard {
-        card: swamp.clone(),
-        owner: id,
-    });
-    hand.push(OwnedCard {
-        card: theatre.clone(),
-        owner: id,
-    });
-    hand.push(OwnedCard {
-        card: grotto.clone(),
-        owner: id,
-    });
+    hand.push(OwnedCard::new(swamp.clone(), id));
+    hand.push(OwnedCard::new(theatre.clone(), id));
+    hand.push(OwnedCard::new(grotto.clone(), id));
+
+    let hand = &player.zones.hand;
+    let selected_name = |is_turn_1: bool, claim_in_play: bool| {
+        let selected = select_land_to_play(hand, is_turn_1, claim_in_play).unwrap();
+        let card = hand.iter().find(|c| c.id == selected).unwrap();
+        card.card.primary_name()
+    };
 
     // On turn 1 we choose theater
-    assert_eq!(
-        select_land_to_play(player, true, false)
-            .unwrap()
-            .card
-            .primary_name(),
-        theatre.primary_name()
-    );
-    player.zones.hand.push(OwnedCard {
-        card: theatre.clone(),
-        owner: id,
-    });
+    assert_eq!(selected_name(true, false), theatre.primary_name());
 
     // Before claim is in play we prefer an untapped black source
-    assert_eq!(
-        select_land_to_play(player, false, false)
-            .unwrap()
-            .card
-            .primary_name(),
-        swamp.primary_name()
-    );
-    player.zones.hand.push(OwnedCard {
-        card: swamp.clone(),
-        owner: id,
-    });
+    assert_eq!(selected_name(false, false), swamp.primary_name());
 
     // After Claim is in play we prefer to scry
-    assert_eq!(
-        select_land_to_play(player, false, true)
-            .unwrap()
-            .card
-            .primary_name(),
-        grotto.primary_name()
-    );
-    player.zones.hand.push(OwnedCard {
-        card: grotto.clone(),
-        owner: id,
-    });
+    assert_eq!(selected_name(false, true), grotto.primary_name());
 }